@@ -38,6 +38,7 @@ pub async fn login(
         JwtPayload {
             level: Level::Admin,
             exp: expire.timestamp(),
+            username: Some(user.username),
         },
     )
     .map_err(|e| Error::Bug(format!("{:?}", e)))
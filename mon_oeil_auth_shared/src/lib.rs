@@ -6,6 +6,8 @@ use serde_json::json;
 pub struct JwtPayload {
     pub level: Level,
     pub exp: i64,
+    #[serde(default)]
+    pub username: Option<String>,
 }
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub enum Level {
@@ -49,11 +51,14 @@ pub enum JwtValidationError {
     BadFormat,
 }
 
-pub fn valid_jwt_admin(hs256_private_key: &str, jwt: &str) -> Result<(), JwtValidationError> {
+pub fn valid_jwt_admin(
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<JwtPayload, JwtValidationError> {
     let user = decode_jwt(hs256_private_key, jwt)?;
 
     match user.level {
-        Level::Admin => Ok(()),
+        Level::Admin => Ok(user),
         // _ => Err(ApiError("Sorry u cant do that :(".to_owned())),
     }
 }
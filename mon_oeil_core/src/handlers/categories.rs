@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use super::languages::known_langs;
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// tree of every category
+pub async fn get_categories(db: &db::GestureClientPool) -> Result<Vec<Category>, Error> {
+    let client = db.get().await.map_err(Error::from)?;
+    let categories = client.categories().await?;
+    Ok(categories.into_iter().map(From::from).collect())
+}
+
+/// add a category as auth user, return its id
+pub async fn post_category(
+    db: &db::GestureClientPool,
+    mut new_category: NewCategory,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<String, Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_category.labels = known_labels(&client, new_category.labels).await?;
+    client.set_author(jwt_payload.username);
+    client
+        .add_category(new_category.into())
+        .await
+        .map_err(Error::from)
+}
+
+/// move, reorder or relabel a category as auth user
+pub async fn put_category(
+    db: &db::GestureClientPool,
+    id: &str,
+    mut category: NewCategory,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    category.labels = known_labels(&client, category.labels).await?;
+    client.set_author(jwt_payload.username);
+    client
+        .update_category(id, category.into())
+        .await
+        .map_err(Error::from)
+}
+
+/// remove a category and its subcategories as auth user, their gestures are kept
+pub async fn delete_category(
+    db: &db::GestureClientPool,
+    id: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client.delete_category(id).await.map_err(Error::from)
+}
+
+/// replace the categories of a gesture as auth user
+pub async fn put_gesture_categories(
+    db: &db::GestureClientPool,
+    id_gesture: &str,
+    gesture_categories: GestureCategories,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .set_gesture_categories(id_gesture, gesture_categories.categories)
+        .await
+        .map_err(Error::from)
+}
+
+/// Labels keyed by the code of their lang, a category needs at least one label
+async fn known_labels(
+    client: &db::GestureClient,
+    labels: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, Error> {
+    if labels.values().all(|label| label.trim().is_empty()) {
+        return Err(Error::NotAccepted(
+            "A category needs a label in at least one lang".to_owned(),
+        ));
+    }
+    if labels.values().any(|label| label.trim().is_empty()) {
+        return Err(Error::NotAccepted("A label can't be empty".to_owned()));
+    }
+
+    let (langs, labels): (Vec<String>, Vec<String>) = labels.into_iter().unzip();
+    let langs = known_langs(client, langs).await?;
+    Ok(langs
+        .into_iter()
+        .zip(labels.into_iter().map(|label| label.trim().to_owned()))
+        .collect())
+}
@@ -1,3 +1,5 @@
+use super::expected_version;
+use super::languages::known_langs;
 use crate::{models::*, Error};
 use mon_oeil_auth_shared::valid_jwt_admin;
 use mon_oeil_db as db;
@@ -6,31 +8,38 @@ use mon_oeil_db as db;
 pub async fn post_description(
     db: &db::GestureClientPool,
     id_gesture: &str,
-    new_description: NewDescription,
+    mut new_description: NewDescription,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<String, Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_description.langs = known_langs(&client, new_description.langs).await?;
+    client.set_author(jwt_payload.username);
     client
         .add_description(new_description.into(), &id_gesture)
         .await
         .map_err(Error::from)
 }
 
+/// update description as auth user if it is still at the If-Match version, return the new version
 pub async fn put_description(
     db: &db::GestureClientPool,
     id: &str,
-    new_description: NewDescription,
+    mut new_description: NewDescription,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
-) -> Result<(), Error> {
-    valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_description.langs = known_langs(&client, new_description.langs).await?;
+    client.set_author(jwt_payload.username);
     client
-        .update_description(id, new_description.into())
+        .update_description(id, new_description.into(), version)
         .await
         .map_err(Error::from)
 }
@@ -38,14 +47,17 @@ pub async fn put_description(
 pub async fn delete_description(
     db: &db::GestureClientPool,
     id: &str,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<(), Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
     client
-        .delete_description_cascade(&id)
+        .delete_description_cascade(&id, version)
         .await
         .map_err(Error::from)
 }
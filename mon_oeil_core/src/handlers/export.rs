@@ -0,0 +1,203 @@
+use chrono::NaiveDateTime;
+use std::collections::{BTreeMap, HashMap};
+
+use super::merge_db_and_storage;
+use super::pictures::upload_before;
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+use mon_oeil_storage::*;
+
+/// Export every gesture as auth admin, see `export_all_gestures`
+pub async fn export_gestures(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<GesturesExport, Error> {
+    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    export_all_gestures(db, storage).await
+}
+
+/// Languages, category tree and every gesture newest first, read page by page, with the creation
+/// dates of their parts and the files of their pictures
+pub async fn export_all_gestures(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+) -> Result<GesturesExport, Error> {
+    let client = db.get().await.map_err(Error::from)?;
+    let mut export = GesturesExport {
+        languages: client
+            .languages()
+            .await?
+            .into_iter()
+            .map(From::from)
+            .collect(),
+        categories: client
+            .categories()
+            .await?
+            .into_iter()
+            .map(From::from)
+            .collect(),
+        gestures: vec![],
+        creation_dates: BTreeMap::new(),
+        pictures: vec![],
+    };
+    let mut after = None;
+
+    loop {
+        let page = client
+            .all_gestures(
+                db::PaginationRequest::Keyset {
+                    max: MAX_PAGE_SIZE,
+                    after,
+                },
+                None,
+                db::FilterRequest::default(),
+                db::Sort::Newest,
+                false,
+            )
+            .await?;
+
+        for gesture in page.gestures {
+            export
+                .pictures
+                .extend(gesture.pictures.iter().map(|picture| PictureFile {
+                    id: picture.id.clone(),
+                    format: picture.format.clone(),
+                }));
+            export.gestures.push(merge_db_and_storage(gesture, storage));
+        }
+
+        match page.next_cursor {
+            Some(cursor) => after = Some(cursor),
+            _ => break,
+        }
+    }
+
+    // dates of parts created meanwhile are not used by any exported gesture
+    export.creation_dates = client.creation_dates().await?.into_iter().collect();
+    Ok(export)
+}
+
+/// Upload the picture files of exported gestures then add them back with their ids, creation
+/// dates and versions, after the missing languages and categories
+/// Nothing is added, and uploaded files are deleted, when a gesture already exists, belongs to an
+/// unknown category, a picture file is missing or an upload fails
+pub async fn restore_gestures(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    languages: Vec<Language>,
+    categories: Vec<Category>,
+    gestures: Vec<Gesture>,
+    creation_dates: BTreeMap<String, NaiveDateTime>,
+    files: Vec<(PictureFile, Vec<u8>)>,
+    author: Option<String>,
+) -> Result<(), Error> {
+    let mut files: HashMap<String, (String, Vec<u8>)> = files
+        .into_iter()
+        .map(|(file, content)| (file.id, (file.format, content)))
+        .collect();
+
+    let mut uploads = Vec::new();
+    let mut gestures_db = Vec::with_capacity(gestures.len());
+    for gesture in gestures {
+        let Gesture {
+            id,
+            version,
+            tags,
+            descriptions,
+            meanings,
+            pictures,
+            categories,
+            related,
+            ..
+        } = gesture;
+
+        let mut pictures_db = Vec::with_capacity(pictures.len());
+        for Picture {
+            id,
+            version,
+            langs,
+            source,
+            ..
+        } in pictures
+        {
+            let (format, content) = files.remove(&id).ok_or_else(|| {
+                Error::NotAccepted(format!("The file of picture {} is missing", id))
+            })?;
+            uploads.push((id.clone(), format.clone(), content));
+            pictures_db.push(db::Picture {
+                id,
+                version,
+                langs,
+                format,
+                source: source.map(From::from),
+            });
+        }
+
+        gestures_db.push(db::Gesture {
+            id,
+            version,
+            tags,
+            descriptions: descriptions.into_iter().map(From::from).collect(),
+            meanings: meanings.into_iter().map(From::from).collect(),
+            pictures: pictures_db,
+            categories,
+            related: related.into_iter().map(Into::into).collect(),
+            search_match: None,
+        });
+    }
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    // the file of an existing picture would be replaced, then deleted with the failed restore
+    for (id, _, _) in &uploads {
+        match client.get_picture_format(id).await {
+            Err(db::DbError::NotFound) => {}
+            Ok(_) => {
+                return Err(Error::NotAccepted(format!(
+                    "The picture {} already exists",
+                    id
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    client.set_author(author);
+    // files are uploaded before the rows are committed, then deleted if the restore fails
+    upload_before(storage, uploads, async {
+        client
+            .restore_gestures(
+                languages.into_iter().map(From::from).collect(),
+                categories
+                    .into_iter()
+                    .map(|category| category_db(category, None))
+                    .collect(),
+                gestures_db,
+                creation_dates.into_iter().collect(),
+            )
+            .await
+            .map_err(Error::from)
+    })
+    .await
+}
+
+/// A category of the exported tree with the id of its parent, like the db gives it
+fn category_db(category: Category, id_parent: Option<String>) -> db::Category {
+    let Category {
+        id,
+        position,
+        labels,
+        children,
+    } = category;
+    db::Category {
+        children: children
+            .into_iter()
+            .map(|child| category_db(child, Some(id.clone())))
+            .collect(),
+        id,
+        id_parent,
+        position,
+        labels,
+    }
+}
@@ -1,40 +1,324 @@
+use super::expected_version;
+use super::languages::known_langs;
+use super::meanings::{region_code, valid_register};
 use crate::{models::*, Error};
+use chrono::Utc;
 use mon_oeil_auth_shared::valid_jwt_admin;
 use mon_oeil_db as db;
 use mon_oeil_storage::*;
 
+/// Page of gestures, with the `lang` param or `accept_language` when `negotiate` is asked, their
+/// content is only in the first of these langs they have, or else in the first of `fallback_langs`
 pub async fn get_gestures(
     db: &db::GestureClientPool,
     storage: &Storage,
     search_param: SearchParam,
-) -> Result<(Vec<Gesture>, u16), Error> {
+    accept_language: Option<&str>,
+    fallback_langs: &[String],
+) -> Result<GesturesPage, Error> {
+    let SearchParam {
+        max,
+        page,
+        cursor,
+        search,
+        lang,
+        tags,
+        has_pictures,
+        created_after,
+        category,
+        region,
+        register,
+        facets,
+        sort,
+        seed,
+        negotiate,
+    } = search_param;
+
+    if max == 0 || max > MAX_PAGE_SIZE {
+        return Err(Error::NotAccepted(format!(
+            "max should be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    if page == 0 {
+        return Err(Error::NotAccepted("page starts at 1".to_owned()));
+    }
+    let region = region.as_deref().map(region_code).transpose()?;
+    if let Some(register) = &register {
+        valid_register(register)?;
+    }
+    let pagination = match cursor {
+        Some(_) if search.is_some() => {
+            return Err(Error::NotAccepted(
+                "cursor can't be used with search, results are ordered by relevance".to_owned(),
+            ))
+        }
+        Some(_) if !matches!(sort, None | Some(Sort::Relevance) | Some(Sort::Newest)) => {
+            return Err(Error::NotAccepted(
+                "cursor can only list the newest gestures first".to_owned(),
+            ))
+        }
+        Some(cursor) if cursor.is_empty() => db::PaginationRequest::Keyset { max, after: None },
+        Some(cursor) => db::PaginationRequest::Keyset {
+            max,
+            after: Some(cursor.parse()?),
+        },
+        _ => db::PaginationRequest::Page { max, page },
+    };
+
     let gestures = db.get().await.map_err(Error::from)?;
-    let (gestures, total) = gestures
+    // stored langs have the case of the languages table, like en-GB for en-gb
+    let lang = match lang {
+        Some(lang) => Some(known_langs(&gestures, vec![lang]).await?.remove(0)),
+        _ => None,
+    };
+
+    let sort = match sort {
+        None | Some(Sort::Relevance) => db::Sort::Relevance,
+        Some(Sort::Newest) => db::Sort::Newest,
+        Some(Sort::Oldest) => db::Sort::Oldest,
+        Some(Sort::Updated) => db::Sort::LastUpdated,
+        Some(Sort::Tag) => db::Sort::Tag,
+        Some(Sort::Meaning) => db::Sort::Meaning { lang: lang.clone() },
+        Some(Sort::Random) => db::Sort::Random { seed },
+    };
+
+    // the lang param is an explicit choice, it wins over the browser langs
+    let requested_langs = match &lang {
+        Some(lang) => vec![lang.to_lowercase()],
+        _ if negotiate => accept_language.map(accepted_langs).unwrap_or_default(),
+        _ => vec![],
+    };
+
+    let db::GesturesPage {
+        gestures,
+        total,
+        facets,
+        next_cursor,
+    } = gestures
         .all_gestures(
-            db::PaginationRequest {
-                max: search_param.max,
-                page: search_param.page,
+            pagination,
+            search.map(|text| db::SearchRequest {
+                text,
+                lang: lang.clone(),
+            }),
+            db::FilterRequest {
+                tags,
+                lang,
+                has_pictures,
+                created_after,
+                category,
+                region,
+                register,
             },
-            search_param.search,
+            sort,
+            facets,
         )
         .await?;
-    let gestures = gestures
+    let mut items: Vec<Gesture> = gestures
         .into_iter()
         .map(|gesture_db| merge_db_and_storage(gesture_db, &storage))
         .collect();
-    Ok((gestures, total))
+
+    let mut content_langs = Vec::new();
+    if !requested_langs.is_empty() {
+        let mut preferred_langs = requested_langs;
+        for lang in fallback_langs {
+            if !preferred_langs.contains(lang) {
+                preferred_langs.push(lang.clone());
+            }
+        }
+        for gesture in &mut items {
+            if let Some(lang) = negotiate_lang(gesture, &preferred_langs) {
+                if !content_langs.contains(&lang) {
+                    content_langs.push(lang);
+                }
+            }
+        }
+    }
+
+    Ok(GesturesPage {
+        items,
+        total,
+        next_cursor: next_cursor.map(|cursor| cursor.to_string()),
+        facets: facets.map(From::from),
+        content_langs,
+    })
+}
+
+/// Langs of an Accept-Language header, most wanted first, without the wildcard
+fn accepted_langs(accept_language: &str) -> Vec<String> {
+    let mut langs: Vec<(String, f32)> = accept_language
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let lang = parts.next()?.trim().to_lowercase();
+            let quality = match parts.find_map(|part| part.trim().strip_prefix("q=")) {
+                Some(quality) => quality.trim().parse().ok()?,
+                _ => 1.0,
+            };
+            if lang.is_empty() || lang == "*" || quality <= 0.0 {
+                None
+            } else {
+                Some((lang, quality))
+            }
+        })
+        .collect();
+    // stable sort, langs of the same quality keep the order of the header
+    langs.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    langs.into_iter().map(|(lang, _)| lang).collect()
+}
+
+/// `fr` content is served for `fr-CA` and the other way around
+fn lang_matches(content_lang: &str, wanted: &str) -> bool {
+    let content_lang = content_lang.to_lowercase();
+    content_lang == wanted
+        || wanted.starts_with(&format!("{}-", content_lang))
+        || content_lang.starts_with(&format!("{}-", wanted))
+}
+
+/// Keep only the content in the first preferred lang the gesture has and return this lang,
+/// the gesture is left whole when it has none of them
+fn negotiate_lang(gesture: &mut Gesture, preferred_langs: &[String]) -> Option<String> {
+    let has = |langs: &[String], wanted: &str| langs.iter().any(|lang| lang_matches(lang, wanted));
+
+    let gesture_langs: Vec<&String> = gesture
+        .descriptions
+        .iter()
+        .flat_map(|description| {
+            description.langs.iter().chain(
+                description
+                    .meanings
+                    .iter()
+                    .flat_map(|meaning| meaning.langs.iter()),
+            )
+        })
+        .chain(
+            gesture
+                .meanings
+                .iter()
+                .flat_map(|meaning| meaning.langs.iter()),
+        )
+        .chain(
+            gesture
+                .pictures
+                .iter()
+                .flat_map(|picture| picture.langs.iter()),
+        )
+        .collect();
+    let (wanted, served) = preferred_langs.iter().find_map(|wanted| {
+        gesture_langs
+            .iter()
+            .find(|lang| lang_matches(lang, wanted))
+            .map(|lang| (wanted.clone(), lang.to_lowercase()))
+    })?;
+
+    gesture
+        .descriptions
+        .retain(|description| has(&description.langs, &wanted));
+    for description in &mut gesture.descriptions {
+        description
+            .meanings
+            .retain(|meaning| has(&meaning.langs, &wanted));
+    }
+    gesture
+        .meanings
+        .retain(|meaning| has(&meaning.langs, &wanted));
+    gesture
+        .pictures
+        .retain(|picture| has(&picture.langs, &wanted));
+
+    Some(served)
+}
+
+/// A random gesture having the tags and content in the lang, to discover gestures
+pub async fn get_random_gesture(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    random_param: RandomParam,
+) -> Result<Gesture, Error> {
+    let RandomParam { tags, lang } = random_param;
+    let lang = match lang {
+        Some(lang) => {
+            let client = db.get().await.map_err(Error::from)?;
+            Some(known_langs(&client, vec![lang]).await?.remove(0))
+        }
+        _ => None,
+    };
+    pick_gesture(
+        db,
+        storage,
+        db::FilterRequest {
+            tags,
+            lang,
+            ..db::FilterRequest::default()
+        },
+        None,
+    )
+    .await
+}
+
+/// The same gesture all day long (UTC), picked again every day
+pub async fn get_daily_gesture(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+) -> Result<Gesture, Error> {
+    let today = Utc::today().naive_utc();
+    pick_gesture(
+        db,
+        storage,
+        db::FilterRequest::default(),
+        Some(today.to_string()),
+    )
+    .await
+}
+
+/// First gesture of the random order of the seed
+async fn pick_gesture(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    filter: db::FilterRequest,
+    seed: Option<String>,
+) -> Result<Gesture, Error> {
+    let gestures = db.get().await.map_err(Error::from)?;
+    let page = gestures
+        .all_gestures(
+            db::PaginationRequest::Page { max: 1, page: 1 },
+            None,
+            filter,
+            db::Sort::Random { seed },
+            false,
+        )
+        .await?;
+    page.gestures
+        .into_iter()
+        .next()
+        .map(|gesture_db| merge_db_and_storage(gesture_db, &storage))
+        .ok_or(Error::NotFound)
 }
 
 pub fn merge_db_and_storage(gesture_db: db::Gesture, storage: &Storage) -> Gesture {
     let db::Gesture {
         id,
+        version,
         tags,
         descriptions,
         meanings,
         pictures,
+        categories,
+        related,
+        search_match,
     } = gesture_db;
+    let (matched_in, snippet) = match search_match {
+        Some(db::SearchMatch {
+            matched_in,
+            snippet,
+        }) => (Some(matched_in), snippet),
+        _ => (None, None),
+    };
     Gesture {
         id,
+        version,
         tags,
         descriptions: descriptions.into_iter().map(From::from).collect(),
         meanings: meanings.into_iter().map(From::from).collect(),
@@ -45,6 +329,10 @@ pub fn merge_db_and_storage(gesture_db: db::Gesture, storage: &Storage) -> Gestu
                 Picture::from(picture_db, storage_url)
             })
             .collect(),
+        categories,
+        related: related.into_iter().map(From::from).collect(),
+        matched_in,
+        snippet,
     }
 }
 
@@ -54,27 +342,32 @@ pub async fn post_gesture(
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<String, Error> {
-    valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
     client
         .add_gesture(new_gesture.into())
         .await
         .map_err(Error::from)
 }
 
+/// update gesture as auth user if it is still at the If-Match version, return the new version
 pub async fn put_gesture(
     db: &db::GestureClientPool,
     id: &str,
     new_gesture: NewGesture,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
-) -> Result<(), Error> {
-    valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
     client
-        .update_gesture(id, new_gesture.into())
+        .update_gesture(id, new_gesture.into(), version)
         .await
         .map_err(Error::from)
 }
@@ -82,14 +375,17 @@ pub async fn put_gesture(
 pub async fn delete_gesture(
     db: &db::GestureClientPool,
     id: &str,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<(), Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
     client
-        .delete_gesture_cascade(&id)
+        .delete_gesture_cascade(&id, version)
         .await
         .map_err(Error::from)
 }
@@ -0,0 +1,34 @@
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// every revision of a gesture and its nested data as auth user
+pub async fn get_gesture_history(
+    db: &db::GestureClientPool,
+    id_gesture: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<Vec<Revision>, Error> {
+    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let client = db.get().await.map_err(Error::from)?;
+    let revisions = client.gesture_history(id_gesture).await?;
+    Ok(revisions.into_iter().map(From::from).collect())
+}
+
+/// restore an entity as it was at a revision as auth user
+pub async fn revert_revision(
+    db: &db::GestureClientPool,
+    id_revision: i64,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .revert_revision(id_revision)
+        .await
+        .map_err(Error::from)
+}
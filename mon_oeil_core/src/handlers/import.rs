@@ -0,0 +1,565 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::languages::canonical_langs;
+use super::pictures::{upload_before, valid_format};
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+use mon_oeil_storage::*;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+
+/// Gestures of an import file, or every error found in it
+///
+/// JSON and YAML files are a list of gestures:
+///
+/// ```yaml
+/// - tags: [insulte, main]
+///   descriptions:
+///     - value: Le majeur levé, les autres doigts repliés
+///       langs: [fr]
+///       meanings:
+///         - value: Va te faire voir
+///           langs: [fr]
+///   meanings:
+///     - value: Une insulte
+///       langs: [fr, en]
+///   pictures:
+///     - path: pictures/majeur.png
+///       langs: [fr]
+/// ```
+///
+/// CSV files have a header and one line per gesture, description, meaning or picture:
+///
+/// ```csv
+/// gesture,description,kind,value,langs,path
+/// p12,,gesture,insulte;main,,
+/// p12,d1,description,"Le majeur levé, les autres doigts repliés",fr,
+/// p12,d1,meaning,Va te faire voir,fr,
+/// p12,,meaning,Une insulte,fr;en,
+/// p12,,picture,,fr,pictures/majeur.png
+/// ```
+///
+/// - `gesture` is a key of the gesture, like its page in the book, its `gesture` line comes first
+/// - `description` is a key of the description, a `meaning` line with it belongs to the description
+/// - tags and langs are separated by `;`
+pub fn parse_import(
+    format: ImportFormat,
+    content: &[u8],
+) -> Result<Vec<ImportGesture>, Vec<ImportError>> {
+    let mut gestures: Vec<ImportGesture> = match format {
+        ImportFormat::Json => serde_json::from_slice(content)
+            .map_err(|err| vec![syntax_error(Some(err.line()), &err)])?,
+        ImportFormat::Yaml => serde_yaml::from_slice(content)
+            .map_err(|err| vec![syntax_error(err.location().map(|l| l.line()), &err)])?,
+        ImportFormat::Csv => return parse_csv(content),
+    };
+    set_lines(&mut gestures, content);
+
+    let mut errors = Vec::new();
+    for (i_g, gesture) in gestures.iter().enumerate() {
+        let path = format!("gestures[{}]", i_g);
+        push_errors(
+            &mut errors,
+            &entry_location(gesture.line, &path),
+            gesture_errors(gesture),
+        );
+        for (i_d, description) in gesture.descriptions.iter().enumerate() {
+            let path = format!("{}.descriptions[{}]", path, i_d);
+            push_errors(
+                &mut errors,
+                &entry_location(description.line, &path),
+                text_errors(&description.value, &description.langs),
+            );
+            for (i_m, meaning) in description.meanings.iter().enumerate() {
+                push_errors(
+                    &mut errors,
+                    &entry_location(meaning.line, &format!("{}.meanings[{}]", path, i_m)),
+                    text_errors(&meaning.value, &meaning.langs),
+                );
+            }
+        }
+        for (i_m, meaning) in gesture.meanings.iter().enumerate() {
+            push_errors(
+                &mut errors,
+                &entry_location(meaning.line, &format!("{}.meanings[{}]", path, i_m)),
+                text_errors(&meaning.value, &meaning.langs),
+            );
+        }
+        for (i_p, picture) in gesture.pictures.iter().enumerate() {
+            push_errors(
+                &mut errors,
+                &entry_location(picture.line, &format!("{}.pictures[{}]", path, i_p)),
+                picture_errors(picture),
+            );
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(gestures)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse an import file then import its gestures as auth admin, see `import_gestures_as`
+pub async fn import_gestures(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    format: ImportFormat,
+    content: &[u8],
+    files: HashMap<String, Vec<u8>>,
+    dry_run: bool,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<ImportReport, Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    match parse_import(format, content) {
+        Ok(gestures) => {
+            import_gestures_as(db, storage, gestures, files, dry_run, jwt_payload.username).await
+        }
+        Err(errors) => Ok(ImportReport {
+            dry_run,
+            errors,
+            ..ImportReport::default()
+        }),
+    }
+}
+
+/// Upload the pictures of parsed gestures from `files` by path then add them in a single transaction
+/// Nothing is added, and uploaded files are deleted, when a picture file is missing, a lang is
+/// unknown, an upload fails or the db refuses a gesture
+pub async fn import_gestures_as(
+    db: &db::GestureClientPool,
+    storage: &Storage,
+    mut gestures: Vec<ImportGesture>,
+    files: HashMap<String, Vec<u8>>,
+    dry_run: bool,
+    author: Option<String>,
+) -> Result<ImportReport, Error> {
+    let mut report = ImportReport {
+        dry_run,
+        ..ImportReport::default()
+    };
+
+    let mut uploads = Vec::new();
+    for gesture in &gestures {
+        report.gestures += 1;
+        report.descriptions += gesture.descriptions.len();
+        report.meanings += gesture.meanings.len()
+            + gesture
+                .descriptions
+                .iter()
+                .map(|description| description.meanings.len())
+                .sum::<usize>();
+        report.pictures += gesture.pictures.len();
+
+        for picture in &gesture.pictures {
+            match files.get(&picture.path) {
+                Some(content) => uploads.push(content.clone()),
+                _ => report.errors.push(ImportError {
+                    location: picture.path.clone(),
+                    message: "picture file not found".to_owned(),
+                }),
+            }
+        }
+    }
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    let languages = client.languages().await?;
+    for (i_g, gesture) in gestures.iter_mut().enumerate() {
+        let path = format!("gestures[{}]", i_g);
+        for (i_d, description) in gesture.descriptions.iter_mut().enumerate() {
+            let path = format!("{}.descriptions[{}]", path, i_d);
+            let location = entry_location(description.line, &path);
+            check_langs(&languages, &mut description.langs, &location, &mut report);
+            for (i_m, meaning) in description.meanings.iter_mut().enumerate() {
+                let location = entry_location(meaning.line, &format!("{}.meanings[{}]", path, i_m));
+                check_langs(&languages, &mut meaning.langs, &location, &mut report);
+            }
+        }
+        for (i_m, meaning) in gesture.meanings.iter_mut().enumerate() {
+            let location = entry_location(meaning.line, &format!("{}.meanings[{}]", path, i_m));
+            check_langs(&languages, &mut meaning.langs, &location, &mut report);
+        }
+        for (i_p, picture) in gesture.pictures.iter_mut().enumerate() {
+            let location = entry_location(picture.line, &format!("{}.pictures[{}]", path, i_p));
+            check_langs(&languages, &mut picture.langs, &location, &mut report);
+        }
+    }
+    if !report.errors.is_empty() {
+        return Ok(report);
+    }
+
+    client.set_author(author);
+    let trees: Vec<db::NewGestureTree> = gestures.into_iter().map(From::from).collect();
+    if dry_run {
+        client.import_gestures(trees, dry_run).await?;
+        return Ok(report);
+    }
+
+    // files are uploaded before the rows are committed, then deleted if the import fails
+    let files = trees
+        .iter()
+        .flat_map(|tree| &tree.pictures)
+        .zip(uploads)
+        .map(|((id, picture), content)| (id.clone(), picture.format.clone(), content))
+        .collect();
+    let imported = upload_before(storage, files, async {
+        client
+            .import_gestures(trees, false)
+            .await
+            .map_err(Error::from)
+    })
+    .await?;
+    report.ids = imported.into_iter().map(|gesture| gesture.id).collect();
+
+    Ok(report)
+}
+
+/// Replace `langs` by their codes in the languages table, or report the unknown ones
+fn check_langs(
+    languages: &[db::Language],
+    langs: &mut Vec<String>,
+    location: &str,
+    report: &mut ImportReport,
+) {
+    match canonical_langs(languages, std::mem::take(langs)) {
+        Ok(known) => *langs = known,
+        Err(unknown) => report.errors.push(ImportError {
+            location: location.to_owned(),
+            message: format!("unknown langs {}", unknown.join(", ")),
+        }),
+    }
+}
+
+#[derive(Deserialize)]
+struct CsvLine {
+    gesture: String,
+    description: String,
+    kind: String,
+    value: String,
+    langs: String,
+    path: String,
+}
+
+fn parse_csv(content: &[u8]) -> Result<Vec<ImportGesture>, Vec<ImportError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(content);
+    let headers = reader
+        .headers()
+        .map_err(|err| vec![csv_error(&err)])?
+        .clone();
+
+    let mut gestures: Vec<ImportGesture> = Vec::new();
+    // line of each gesture, to report the empty ones
+    let mut gesture_lines = Vec::new();
+    let mut gesture_keys = HashMap::new();
+    let mut description_keys = HashMap::new();
+    let mut errors = Vec::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                errors.push(csv_error(&err));
+                continue;
+            }
+        };
+        let line_number = record.position().map(|p| p.line() as usize);
+        let location = format!("line {}", line_number.unwrap_or_default());
+        let line: CsvLine = match record.deserialize(Some(&headers)) {
+            Ok(line) => line,
+            Err(err) => {
+                errors.push(ImportError {
+                    location,
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        let CsvLine {
+            gesture,
+            description,
+            kind,
+            value,
+            langs,
+            path,
+        } = line;
+        let langs = split_list(&langs);
+
+        if kind == "gesture" {
+            if gesture_keys.contains_key(&gesture) {
+                push_errors(
+                    &mut errors,
+                    &location,
+                    vec![format!("gesture {} is already defined", gesture)],
+                );
+                continue;
+            }
+            gesture_keys.insert(gesture, gestures.len());
+            gesture_lines.push(location);
+            gestures.push(ImportGesture {
+                tags: split_list(&value),
+                line: line_number,
+                ..ImportGesture::default()
+            });
+            continue;
+        }
+
+        let i_g = match gesture_keys.get(&gesture) {
+            Some(i_g) => *i_g,
+            _ => {
+                push_errors(
+                    &mut errors,
+                    &location,
+                    vec![format!(
+                        "gesture {} is not defined on a line above",
+                        gesture
+                    )],
+                );
+                continue;
+            }
+        };
+        match kind.as_str() {
+            "description" => {
+                if description.is_empty()
+                    || description_keys.contains_key(&(i_g, description.clone()))
+                {
+                    push_errors(
+                        &mut errors,
+                        &location,
+                        vec!["a description needs a key unique in its gesture".to_owned()],
+                    );
+                    continue;
+                }
+                push_errors(&mut errors, &location, text_errors(&value, &langs));
+                description_keys.insert((i_g, description), gestures[i_g].descriptions.len());
+                gestures[i_g].descriptions.push(ImportDescription {
+                    value,
+                    langs,
+                    meanings: vec![],
+                    line: line_number,
+                });
+            }
+            "meaning" => {
+                push_errors(&mut errors, &location, text_errors(&value, &langs));
+                let meaning = ImportMeaning {
+                    value,
+                    langs,
+                    line: line_number,
+                };
+                if description.is_empty() {
+                    gestures[i_g].meanings.push(meaning);
+                } else if let Some(i_d) = description_keys.get(&(i_g, description.clone())) {
+                    gestures[i_g].descriptions[*i_d].meanings.push(meaning);
+                } else {
+                    push_errors(
+                        &mut errors,
+                        &location,
+                        vec![format!(
+                            "description {} is not defined on a line above",
+                            description
+                        )],
+                    );
+                }
+            }
+            "picture" => {
+                let picture = ImportPicture {
+                    path,
+                    langs,
+                    line: line_number,
+                };
+                push_errors(&mut errors, &location, picture_errors(&picture));
+                gestures[i_g].pictures.push(picture);
+            }
+            _ => push_errors(
+                &mut errors,
+                &location,
+                vec![format!(
+                    "kind {} is not gesture, description, meaning or picture",
+                    kind
+                )],
+            ),
+        }
+    }
+
+    for (gesture, location) in gestures.iter().zip(gesture_lines) {
+        push_errors(&mut errors, &location, gesture_errors(gesture));
+    }
+
+    if errors.is_empty() {
+        Ok(gestures)
+    } else {
+        Err(errors)
+    }
+}
+
+fn gesture_errors(gesture: &ImportGesture) -> Vec<String> {
+    let mut errors = Vec::new();
+    if gesture.tags.iter().any(|tag| tag.trim().is_empty()) {
+        errors.push("tags can't be empty".to_owned());
+    }
+    if gesture.descriptions.is_empty() && gesture.meanings.is_empty() && gesture.pictures.is_empty()
+    {
+        errors.push("gesture has no description, meaning or picture".to_owned());
+    }
+    errors
+}
+
+fn text_errors(value: &str, langs: &[String]) -> Vec<String> {
+    let mut errors = Vec::new();
+    if value.trim().is_empty() {
+        errors.push("value is empty".to_owned());
+    }
+    errors.extend(langs_errors(langs));
+    errors
+}
+
+fn picture_errors(picture: &ImportPicture) -> Vec<String> {
+    let mut errors = Vec::new();
+    if picture.path.trim().is_empty() {
+        errors.push("path is empty".to_owned());
+    } else if let Err(Error::NotAccepted(message)) = valid_format(&picture.format()) {
+        errors.push(message);
+    }
+    errors.extend(langs_errors(&picture.langs));
+    errors
+}
+
+fn langs_errors(langs: &[String]) -> Vec<String> {
+    if langs.is_empty() {
+        vec!["langs are missing".to_owned()]
+    } else if langs.iter().any(|lang| lang.trim().is_empty()) {
+        vec!["langs can't be empty".to_owned()]
+    } else {
+        vec![]
+    }
+}
+
+fn push_errors(errors: &mut Vec<ImportError>, location: &str, messages: Vec<String>) {
+    errors.extend(messages.into_iter().map(|message| ImportError {
+        location: location.to_owned(),
+        message,
+    }));
+}
+
+/// `line 12, gestures[2].meanings[0]`, the path alone when the line is unknown
+fn entry_location(line: Option<usize>, path: &str) -> String {
+    match line {
+        Some(line) => format!("line {}, {}", line, path),
+        _ => path.to_owned(),
+    }
+}
+
+/// Set the line of each entry of parsed JSON or YAML `gestures`, read again as YAML events since
+/// serde gives no position once parsing succeeded, JSON being YAML too
+fn set_lines(gestures: &mut [ImportGesture], content: &[u8]) {
+    let mut lines = EntryLines::default();
+    let read = match std::str::from_utf8(content) {
+        Ok(content) => Parser::new(content.chars()).load(&mut lines, false).is_ok(),
+        _ => false,
+    };
+    if !read {
+        return;
+    }
+    let line = |path: &str| lines.lines.get(path).copied();
+
+    for (i_g, gesture) in gestures.iter_mut().enumerate() {
+        let path = format!("gestures[{}]", i_g);
+        gesture.line = line(&path);
+        for (i_d, description) in gesture.descriptions.iter_mut().enumerate() {
+            let path = format!("{}.descriptions[{}]", path, i_d);
+            description.line = line(&path);
+            for (i_m, meaning) in description.meanings.iter_mut().enumerate() {
+                meaning.line = line(&format!("{}.meanings[{}]", path, i_m));
+            }
+        }
+        for (i_m, meaning) in gesture.meanings.iter_mut().enumerate() {
+            meaning.line = line(&format!("{}.meanings[{}]", path, i_m));
+        }
+        for (i_p, picture) in gesture.pictures.iter_mut().enumerate() {
+            picture.line = line(&format!("{}.pictures[{}]", path, i_p));
+        }
+    }
+}
+
+/// Line of each map of a file by path, like `gestures[2].meanings[0]`
+#[derive(Default)]
+struct EntryLines {
+    /// Path of each list or map being read, with its next index or the key of its next value
+    nodes: Vec<(String, Node)>,
+    lines: HashMap<String, usize>,
+}
+
+enum Node {
+    List(usize),
+    Map(Option<String>),
+}
+
+impl MarkedEventReceiver for EntryLines {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let path = match &event {
+            Event::Scalar(..)
+            | Event::SequenceStart(_)
+            | Event::MappingStart(_)
+            | Event::Alias(_) => {
+                match self.nodes.last_mut() {
+                    None => "gestures".to_owned(),
+                    Some((path, Node::List(index))) => {
+                        *index += 1;
+                        format!("{}[{}]", path, *index - 1)
+                    }
+                    Some((path, Node::Map(key))) => match key.take() {
+                        Some(key) => format!("{}.{}", path, key),
+                        // a key, the next node is its value
+                        None => {
+                            let name = match &event {
+                                Event::Scalar(name, ..) => name.clone(),
+                                _ => "?".to_owned(),
+                            };
+                            *key = Some(name);
+                            format!("{}.?", path)
+                        }
+                    },
+                }
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                self.nodes.pop();
+                return;
+            }
+            _ => return,
+        };
+
+        match event {
+            Event::SequenceStart(_) => self.nodes.push((path, Node::List(0))),
+            Event::MappingStart(_) => {
+                self.lines.insert(path.clone(), mark.line());
+                self.nodes.push((path, Node::Map(None)));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn syntax_error(line: Option<usize>, err: &dyn std::fmt::Display) -> ImportError {
+    ImportError {
+        location: line.map_or_else(|| "file".to_owned(), |line| format!("line {}", line)),
+        message: err.to_string(),
+    }
+}
+
+fn csv_error(err: &csv::Error) -> ImportError {
+    syntax_error(err.position().map(|p| p.line() as usize), err)
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
@@ -0,0 +1,150 @@
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// every language content can be written in
+pub async fn get_languages(db: &db::GestureClientPool) -> Result<Vec<Language>, Error> {
+    let client = db.get().await.map_err(Error::from)?;
+    let languages = client.languages().await?;
+    Ok(languages.into_iter().map(From::from).collect())
+}
+
+/// add a language as auth user, its code is stored in the BCP 47 case (pt-br becomes pt-BR)
+pub async fn post_language(
+    db: &db::GestureClientPool,
+    language: Language,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<String, Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let code = canonical_code(&language.code).ok_or_else(|| {
+        Error::NotAccepted(format!(
+            "{:?} is not a BCP 47 language tag like fr or pt-BR",
+            language.code
+        ))
+    })?;
+    if language.name.trim().is_empty() {
+        return Err(Error::NotAccepted("The name can't be empty".to_owned()));
+    }
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .add_language(db::Language {
+            code: code.clone(),
+            name: language.name,
+        })
+        .await?;
+    Ok(code)
+}
+
+/// rename a language as auth user
+pub async fn put_language(
+    db: &db::GestureClientPool,
+    code: &str,
+    language_name: LanguageName,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    if language_name.name.trim().is_empty() {
+        return Err(Error::NotAccepted("The name can't be empty".to_owned()));
+    }
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .update_language(code, &language_name.name)
+        .await
+        .map_err(Error::from)
+}
+
+/// remove a language as auth user, content already in this language keeps it
+pub async fn delete_language(
+    db: &db::GestureClientPool,
+    code: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client.delete_language(code).await.map_err(Error::from)
+}
+
+/// Codes of `langs` as stored in the languages table, whatever the case they were typed in
+/// Unknown langs are all reported at once
+pub(super) async fn known_langs(
+    client: &db::GestureClient,
+    langs: Vec<String>,
+) -> Result<Vec<String>, Error> {
+    if langs.is_empty() {
+        return Ok(langs);
+    }
+    let languages = client.languages().await?;
+
+    canonical_langs(&languages, langs).map_err(|unknown| {
+        Error::NotAccepted(format!(
+            "Unknown langs {}, see /languages",
+            unknown.join(", ")
+        ))
+    })
+}
+
+/// Codes of `langs` among `languages`, or the unknown ones quoted
+pub(super) fn canonical_langs(
+    languages: &[db::Language],
+    langs: Vec<String>,
+) -> Result<Vec<String>, Vec<String>> {
+    let mut known = Vec::with_capacity(langs.len());
+    let mut unknown = Vec::new();
+    for lang in langs {
+        match languages
+            .iter()
+            .find(|language| language.code.eq_ignore_ascii_case(&lang))
+        {
+            Some(language) => known.push(language.code.clone()),
+            _ => unknown.push(format!("{:?}", lang)),
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(known)
+    } else {
+        Err(unknown)
+    }
+}
+
+/// BCP 47 case of a tag: language lowercase, script titlecase, region uppercase
+/// None when a subtag can't be part of a tag, the db checks their order
+fn canonical_code(code: &str) -> Option<String> {
+    let mut subtags = code.split('-');
+    let language = subtags.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut canonical = vec![language.to_ascii_lowercase()];
+    for subtag in subtags {
+        let alphabetic = subtag.chars().all(|c| c.is_ascii_alphabetic());
+        let alphanumeric = subtag.chars().all(|c| c.is_ascii_alphanumeric());
+        let numeric = subtag.chars().all(|c| c.is_ascii_digit());
+        canonical.push(match subtag.len() {
+            2 if alphabetic => subtag.to_ascii_uppercase(),
+            3 if numeric => subtag.to_owned(),
+            4 if alphabetic => {
+                let (first, rest) = subtag.split_at(1);
+                first.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+            }
+            4 if alphanumeric && subtag.starts_with(|c: char| c.is_ascii_digit()) => {
+                subtag.to_ascii_lowercase()
+            }
+            5..=8 if alphanumeric => subtag.to_ascii_lowercase(),
+            _ => return None,
+        });
+    }
+
+    Some(canonical.join("-"))
+}
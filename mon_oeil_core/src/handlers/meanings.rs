@@ -1,3 +1,5 @@
+use super::expected_version;
+use super::languages::known_langs;
 use crate::{models::*, Error};
 use mon_oeil_auth_shared::valid_jwt_admin;
 use mon_oeil_db as db;
@@ -5,13 +7,16 @@ use mon_oeil_db as db;
 pub async fn post_gesture_s_meaning(
     db: &db::GestureClientPool,
     id_gesture: &str,
-    new_meaning: NewMeaning,
+    mut new_meaning: NewMeaning,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<String, Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_meaning.langs = known_langs(&client, new_meaning.langs).await?;
+    valid_context(&mut new_meaning)?;
+    client.set_author(jwt_payload.username);
     client
         .add_meaning(new_meaning.into(), Some(&id_gesture), None)
         .await
@@ -21,31 +26,40 @@ pub async fn post_gesture_s_meaning(
 pub async fn post_description_s_meaning(
     db: &db::GestureClientPool,
     id_descirption: &str,
-    new_meaning: NewMeaning,
+    mut new_meaning: NewMeaning,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<String, Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_meaning.langs = known_langs(&client, new_meaning.langs).await?;
+    valid_context(&mut new_meaning)?;
+    client.set_author(jwt_payload.username);
     client
         .add_meaning(new_meaning.into(), None, Some(&id_descirption))
         .await
         .map_err(Error::from)
 }
 
+/// update meaning as auth user if it is still at the If-Match version, return the new version
 pub async fn put_meaning(
     db: &db::GestureClientPool,
     id: &str,
-    new_meaning: NewMeaning,
+    mut new_meaning: NewMeaning,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
-) -> Result<(), Error> {
-    valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_meaning.langs = known_langs(&client, new_meaning.langs).await?;
+    valid_context(&mut new_meaning)?;
+    client.set_author(jwt_payload.username);
     client
-        .update_meaning(id, new_meaning.into())
+        .update_meaning(id, new_meaning.into(), version)
         .await
         .map_err(Error::from)
 }
@@ -53,11 +67,59 @@ pub async fn put_meaning(
 pub async fn delete_meaning(
     db: &db::GestureClientPool,
     id: &str,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<(), Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .delete_meaning(&id, version)
+        .await
+        .map_err(Error::from)
+}
+
+/// Uppercase the regions of a meaning, drop duplicates and check its register
+fn valid_context(meaning: &mut NewMeaning) -> Result<(), Error> {
+    let mut regions = Vec::with_capacity(meaning.regions.len());
+    for region in &meaning.regions {
+        let region = region_code(region)?;
+        if !regions.contains(&region) {
+            regions.push(region);
+        }
+    }
+    meaning.regions = regions;
+
+    if let Some(register) = &meaning.register {
+        valid_register(register)?;
+    }
+    Ok(())
+}
+
+/// ISO 3166-1 alpha-2 code of a country in uppercase, like IT
+pub(super) fn region_code(region: &str) -> Result<String, Error> {
+    let region = region.trim();
+    if region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(region.to_uppercase())
+    } else {
+        Err(Error::NotAccepted(format!(
+            "Invalid region {:?}, expected a 2 letters country code",
+            region
+        )))
+    }
+}
 
-    let client = db.get().await.map_err(Error::from)?;
-    client.delete_meaning(&id).await.map_err(Error::from)
+pub(super) fn valid_register(register: &str) -> Result<(), Error> {
+    if db::REGISTERS.contains(&register) {
+        Ok(())
+    } else {
+        Err(Error::NotAccepted(format!(
+            "Invalid register {:?}, expected one of {}",
+            register,
+            db::REGISTERS.join(", ")
+        )))
+    }
 }
@@ -1,9 +1,46 @@
+mod categories;
 mod descriptions;
+mod export;
 mod gestures;
+mod history;
+mod import;
+mod languages;
 mod meanings;
 mod pictures;
+mod related;
+mod sources;
+mod suggestions;
+mod tags;
+mod translations;
 
+pub use categories::*;
 pub use descriptions::*;
+pub use export::*;
 pub use gestures::*;
+pub use history::*;
+pub use import::*;
+pub use languages::*;
 pub use meanings::*;
 pub use pictures::*;
+pub use related::*;
+pub use sources::*;
+pub use suggestions::*;
+pub use tags::*;
+pub use translations::*;
+
+use crate::Error;
+
+/// Version expected by an If-Match header, None when any version matches (*)
+fn expected_version(if_match: Option<&str>) -> Result<Option<i32>, Error> {
+    let if_match = if_match.ok_or(Error::PreconditionRequired)?.trim();
+    if if_match == "*" {
+        return Ok(None);
+    }
+
+    if_match
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| Error::PreconditionFailed)
+}
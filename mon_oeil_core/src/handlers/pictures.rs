@@ -1,3 +1,8 @@
+use log::error;
+use std::future::Future;
+
+use super::expected_version;
+use super::languages::known_langs;
 use crate::{models::*, Error};
 use mon_oeil_auth_shared::valid_jwt_admin;
 use mon_oeil_db as db;
@@ -7,17 +12,19 @@ pub async fn post_picture(
     db: &db::GestureClientPool,
     storage: &Storage,
     id_gesture: &str,
-    new_picture: NewPicture,
+    mut new_picture: NewPicture,
     content: Vec<u8>,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<String, Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
 
     let format = new_picture.format.clone();
     valid_format(&format)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_picture.langs = known_langs(&client, new_picture.langs).await?;
+    client.set_author(jwt_payload.username);
     let new_id = client.add_picture(new_picture.into(), &id_gesture).await?;
 
     storage.upload(&new_id, content, &format).await?;
@@ -25,31 +32,68 @@ pub async fn post_picture(
     Ok(new_id)
 }
 
+/// replace picture file as auth user if it is still at the If-Match version, return the new version
 pub async fn put_picture_file(
     db: &db::GestureClientPool,
     storage: &Storage,
     id: &str,
     new_picture_file_info: NewPictureFileInfo,
     content: Vec<u8>,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
-) -> Result<(), Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
     let format = new_picture_file_info.format.clone();
     valid_format(&format)?;
 
-    let client = db.get().await.map_err(Error::from)?;
-    client
-        .update_picture_format(&id, new_picture_file_info.into())
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    let version = client
+        .update_picture_format(&id, new_picture_file_info.into(), version)
         .await?;
 
     storage.upload(&id, content, &format).await?;
 
-    Ok(())
+    Ok(version)
+}
+
+/// Upload `files` (id, format, content) then run `write`, the uploaded files are deleted if any step fails
+pub(super) async fn upload_before<T>(
+    storage: &Storage,
+    files: Vec<(String, String, Vec<u8>)>,
+    write: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let mut uploaded = Vec::with_capacity(files.len());
+    let mut result = Ok(());
+    for (id, format, content) in files {
+        if let Err(e) = storage.upload(&id, content, &format).await {
+            result = Err(Error::from(e));
+            break;
+        }
+        uploaded.push((id, format));
+    }
+
+    let result = match result {
+        Ok(()) => write.await,
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        for (id, format) in uploaded {
+            if let Err(e) = storage.delete(&id, &format).await {
+                error!(
+                    "Uploaded picture {}.{} can't be deleted: {:?}",
+                    id, format, e
+                );
+            }
+        }
+    }
+    result
 }
 
-fn valid_format(format: &str) -> Result<(), Error> {
+pub(super) fn valid_format(format: &str) -> Result<(), Error> {
     if format != "png" && format != "jpg" && format != "jpeg" {
         Err(Error::NotAccepted(format!(
             "File format {} not accepted, we use only JPEG and PNG",
@@ -60,18 +104,23 @@ fn valid_format(format: &str) -> Result<(), Error> {
     }
 }
 
+/// update picture meta as auth user if it is still at the If-Match version, return the new version
 pub async fn put_picture_meta(
     db: &db::GestureClientPool,
     id: &str,
-    new_picture_meta: NewPictureMeta,
+    mut new_picture_meta: NewPictureMeta,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
-) -> Result<(), Error> {
-    valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(&hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
 
-    let client = db.get().await.map_err(Error::from)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    new_picture_meta.langs = known_langs(&client, new_picture_meta.langs).await?;
+    client.set_author(jwt_payload.username);
     client
-        .update_picture_meta(id, new_picture_meta.into())
+        .update_picture_meta(id, new_picture_meta.into(), version)
         .await
         .map_err(Error::from)
 }
@@ -80,15 +129,19 @@ pub async fn delete_picture(
     db: &db::GestureClientPool,
     storage: &Storage,
     id: &str,
+    if_match: Option<&str>,
     hs256_private_key: &str,
     jwt: &str,
 ) -> Result<(), Error> {
-    valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
-    let client = db.get().await.map_err(Error::from)?;
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
 
     let format = client.get_picture_format(id).await?;
 
-    storage.delete(&id, &format).await?;
+    // db first so a version mismatch keeps the file
+    client.delete_picture(&id, version).await?;
 
-    client.delete_picture(&id).await.map_err(Error::from)
+    storage.delete(&id, &format).await.map_err(Error::from)
 }
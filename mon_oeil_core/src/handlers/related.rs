@@ -0,0 +1,40 @@
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// Link a gesture to another one as auth admin, it replaces any previous link between them
+pub async fn put_related_gesture(
+    db: &db::GestureClientPool,
+    id_gesture: &str,
+    id_related: &str,
+    relation: GestureRelation,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .set_related_gesture(id_gesture, id_related, relation.kind.into())
+        .await
+        .map_err(Error::from)
+}
+
+/// Remove the link between two gestures as auth admin
+pub async fn delete_related_gesture(
+    db: &db::GestureClientPool,
+    id_gesture: &str,
+    id_related: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .delete_related_gesture(id_gesture, id_related)
+        .await
+        .map_err(Error::from)
+}
@@ -0,0 +1,189 @@
+use super::expected_version;
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// set where a description comes from as auth user if it is still at the If-Match version, return the new version
+pub async fn put_description_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    source: Source,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Description,
+        id,
+        Some(source),
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// clear the source of a description as auth user if it is still at the If-Match version, return the new version
+pub async fn delete_description_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Description,
+        id,
+        None,
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// set where a meaning comes from as auth user if it is still at the If-Match version, return the new version
+pub async fn put_meaning_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    source: Source,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Meaning,
+        id,
+        Some(source),
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// clear the source of a meaning as auth user if it is still at the If-Match version, return the new version
+pub async fn delete_meaning_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Meaning,
+        id,
+        None,
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// set where a picture comes from as auth user if it is still at the If-Match version, return the new version
+pub async fn put_picture_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    source: Source,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Picture,
+        id,
+        Some(source),
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// clear the source of a picture as auth user if it is still at the If-Match version, return the new version
+pub async fn delete_picture_source(
+    db: &db::GestureClientPool,
+    id: &str,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    set_source(
+        db,
+        db::Content::Picture,
+        id,
+        None,
+        if_match,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+async fn set_source(
+    db: &db::GestureClientPool,
+    content: db::Content,
+    id: &str,
+    source: Option<Source>,
+    if_match: Option<&str>,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<i32, Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    let version = expected_version(if_match)?;
+    let source = source.map(valid_source).transpose()?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .set_source(content, id, source.map(From::from), version)
+        .await
+        .map_err(Error::from)
+}
+
+/// Check the kind and the page, blank texts are dropped
+fn valid_source(source: Source) -> Result<Source, Error> {
+    let Source {
+        kind,
+        page,
+        reference,
+        attribution,
+        license,
+    } = source;
+    if !db::SOURCE_KINDS.contains(&kind.as_str()) {
+        return Err(Error::NotAccepted(format!(
+            "Invalid source {:?}, expected one of {}",
+            kind,
+            db::SOURCE_KINDS.join(", ")
+        )));
+    }
+    match page {
+        Some(page) if page < 1 => {
+            return Err(Error::NotAccepted(format!("Invalid page {}", page)));
+        }
+        Some(_) if kind != "book" => {
+            return Err(Error::NotAccepted(
+                "Only a book source has a page".to_owned(),
+            ));
+        }
+        _ => {}
+    }
+
+    let text = |text: Option<String>| {
+        text.map(|text| text.trim().to_owned())
+            .filter(|text| !text.is_empty())
+    };
+    Ok(Source {
+        kind,
+        page,
+        reference: text(reference),
+        attribution: text(attribution),
+        license: text(license),
+    })
+}
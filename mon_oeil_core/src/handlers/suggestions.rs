@@ -0,0 +1,17 @@
+use crate::{models::*, Error};
+use mon_oeil_db as db;
+
+/// tags and meaning phrases starting with the typed text
+pub async fn get_suggestions(
+    db: &db::GestureClientPool,
+    param: SuggestParam,
+) -> Result<Vec<Suggestion>, Error> {
+    let prefix = param.q.trim();
+    if prefix.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let client = db.get().await.map_err(Error::from)?;
+    let suggestions = client.suggestions(prefix, param.max).await?;
+    Ok(suggestions.into_iter().map(From::from).collect())
+}
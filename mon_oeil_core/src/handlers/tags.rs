@@ -0,0 +1,44 @@
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// every tag with its number of gestures, most used first
+pub async fn get_tags(db: &db::GestureClientPool) -> Result<Vec<Tag>, Error> {
+    let client = db.get().await.map_err(Error::from)?;
+    let tags = client.tags().await?;
+    Ok(tags.into_iter().map(From::from).collect())
+}
+
+/// rename a tag on every gesture as auth user, a name already used is a conflict, merge them instead
+pub async fn put_tag(
+    db: &db::GestureClientPool,
+    tag: &str,
+    tag_name: TagName,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client.rename_tag(tag, &tag_name.name).await?;
+    Ok(())
+}
+
+/// replace tags by a single one on every gesture as auth user
+pub async fn merge_tags(
+    db: &db::GestureClientPool,
+    tags_merge: TagsMerge,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+    if tags_merge.tags.is_empty() {
+        return Err(Error::NotAccepted("No tag to merge".to_owned()));
+    }
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client.merge_tags(tags_merge.tags, &tags_merge.into).await?;
+    Ok(())
+}
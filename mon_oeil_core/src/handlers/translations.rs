@@ -0,0 +1,126 @@
+use super::languages::known_langs;
+use crate::{models::*, Error};
+use mon_oeil_auth_shared::valid_jwt_admin;
+use mon_oeil_db as db;
+
+/// make a description the translation of another description of the gesture as auth user
+pub async fn put_description_translation(
+    db: &db::GestureClientPool,
+    id: &str,
+    link: TranslationLink,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    link_translation(
+        db,
+        db::Translatable::Description,
+        id,
+        link,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// remove a description from its translations as auth user
+pub async fn delete_description_translation(
+    db: &db::GestureClientPool,
+    id: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    unlink_translation(
+        db,
+        db::Translatable::Description,
+        id,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// make a meaning the translation of another meaning of the gesture as auth user
+pub async fn put_meaning_translation(
+    db: &db::GestureClientPool,
+    id: &str,
+    link: TranslationLink,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    link_translation(
+        db,
+        db::Translatable::Meaning,
+        id,
+        link,
+        hs256_private_key,
+        jwt,
+    )
+    .await
+}
+
+/// remove a meaning from its translations as auth user
+pub async fn delete_meaning_translation(
+    db: &db::GestureClientPool,
+    id: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    unlink_translation(db, db::Translatable::Meaning, id, hs256_private_key, jwt).await
+}
+
+/// descriptions and meanings still to translate in a lang, those of the newest gestures first
+pub async fn get_missing_translations(
+    db: &db::GestureClientPool,
+    param: MissingTranslationParam,
+) -> Result<MissingTranslations, Error> {
+    let MissingTranslationParam { lang, max, page } = param;
+    if max == 0 || max > MAX_PAGE_SIZE {
+        return Err(Error::NotAccepted(format!(
+            "max should be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    if page == 0 {
+        return Err(Error::NotAccepted("page starts at 1".to_owned()));
+    }
+
+    let client = db.get().await.map_err(Error::from)?;
+    let lang = known_langs(&client, vec![lang]).await?.remove(0);
+    let missing = client.missing_translations(&lang, max, page).await?;
+    Ok(missing.into())
+}
+
+async fn link_translation(
+    db: &db::GestureClientPool,
+    kind: db::Translatable,
+    id: &str,
+    link: TranslationLink,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .link_translation(kind, id, &link.translation_of)
+        .await
+        .map_err(Error::from)
+}
+
+async fn unlink_translation(
+    db: &db::GestureClientPool,
+    kind: db::Translatable,
+    id: &str,
+    hs256_private_key: &str,
+    jwt: &str,
+) -> Result<(), Error> {
+    let jwt_payload = valid_jwt_admin(hs256_private_key, jwt).map_err(Error::from)?;
+
+    let mut client = db.get().await.map_err(Error::from)?;
+    client.set_author(jwt_payload.username);
+    client
+        .unlink_translation(kind, id)
+        .await
+        .map_err(Error::from)
+}
@@ -1,4 +1,5 @@
 use log::error;
+use uuid::Uuid;
 
 use super::*;
 use mon_oeil_auth_shared as auth;
@@ -9,33 +10,355 @@ impl From<db::Description> for Description {
     fn from(item: db::Description) -> Self {
         let db::Description {
             id,
+            version,
             value,
             langs,
             meanings,
+            translation_group,
+            source,
         } = item;
         Self {
             id,
+            version,
             value,
             langs,
             meanings: meanings.into_iter().map(From::from).collect(),
+            translation_group,
+            source: source.map(From::from),
         }
     }
 }
 
 impl From<db::Meaning> for Meaning {
     fn from(item: db::Meaning) -> Self {
-        let db::Meaning { id, value, langs } = item;
-        Self { id, value, langs }
+        let db::Meaning {
+            id,
+            version,
+            value,
+            langs,
+            translation_group,
+            regions,
+            register,
+            source,
+        } = item;
+        Self {
+            id,
+            version,
+            value,
+            langs,
+            translation_group,
+            regions,
+            register,
+            source: source.map(From::from),
+        }
+    }
+}
+
+impl From<db::Facets> for Facets {
+    fn from(item: db::Facets) -> Self {
+        let counts = |counts: Vec<(String, i64)>| {
+            counts
+                .into_iter()
+                .map(|(value, count)| FacetCount { value, count })
+                .collect()
+        };
+        Self {
+            tags: counts(item.tags),
+            langs: counts(item.langs),
+        }
+    }
+}
+
+impl From<db::Suggestion> for Suggestion {
+    fn from(item: db::Suggestion) -> Self {
+        let db::Suggestion { kind, value, count } = item;
+        Self { kind, value, count }
+    }
+}
+
+impl From<db::RelatedGesture> for RelatedGesture {
+    fn from(item: db::RelatedGesture) -> Self {
+        let db::RelatedGesture { id, kind } = item;
+        Self { id, kind }
+    }
+}
+
+impl Into<db::RelatedGesture> for RelatedGesture {
+    fn into(self) -> db::RelatedGesture {
+        let Self { id, kind } = self;
+        db::RelatedGesture { id, kind }
+    }
+}
+
+impl Into<db::Relation> for Relation {
+    fn into(self) -> db::Relation {
+        match self {
+            Relation::SeeAlso => db::Relation::SeeAlso,
+            Relation::VariantOf => db::Relation::VariantOf,
+            Relation::HasVariant => db::Relation::HasVariant,
+            Relation::OppositeOf => db::Relation::OppositeOf,
+        }
+    }
+}
+
+impl From<db::Category> for Category {
+    fn from(item: db::Category) -> Self {
+        let db::Category {
+            id,
+            position,
+            labels,
+            children,
+            ..
+        } = item;
+        Self {
+            id,
+            position,
+            labels,
+            children: children.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+impl Into<db::NewCategory> for NewCategory {
+    fn into(self) -> db::NewCategory {
+        let Self {
+            parent,
+            position,
+            labels,
+        } = self;
+        db::NewCategory {
+            id_parent: parent,
+            position,
+            labels,
+        }
+    }
+}
+
+impl From<db::Tag> for Tag {
+    fn from(item: db::Tag) -> Self {
+        let db::Tag { name, count } = item;
+        Self { name, count }
+    }
+}
+
+impl From<db::MissingTranslation> for MissingTranslation {
+    fn from(item: db::MissingTranslation) -> Self {
+        let db::MissingTranslation {
+            kind,
+            id,
+            id_gesture,
+            value,
+            langs,
+        } = item;
+        Self {
+            kind,
+            id,
+            id_gesture,
+            value,
+            langs,
+        }
+    }
+}
+
+impl From<db::MissingTranslations> for MissingTranslations {
+    fn from(item: db::MissingTranslations) -> Self {
+        Self {
+            items: item.items.into_iter().map(From::from).collect(),
+            total: item.total,
+        }
+    }
+}
+
+impl From<db::Language> for Language {
+    fn from(item: db::Language) -> Self {
+        let db::Language { code, name } = item;
+        Self { code, name }
+    }
+}
+
+impl From<Language> for db::Language {
+    fn from(item: Language) -> Self {
+        let Language { code, name } = item;
+        Self { code, name }
+    }
+}
+
+impl From<ImportGesture> for db::NewGestureTree {
+    fn from(item: ImportGesture) -> Self {
+        let ImportGesture {
+            tags,
+            descriptions,
+            meanings,
+            pictures,
+            ..
+        } = item;
+        let meaning = |ImportMeaning { value, langs, .. }| db::NewMeaning {
+            value,
+            langs,
+            regions: vec![],
+            register: None,
+        };
+        Self {
+            gesture: db::NewGesture { tags },
+            descriptions: descriptions
+                .into_iter()
+                .map(|description| {
+                    let ImportDescription {
+                        value,
+                        langs,
+                        meanings,
+                        ..
+                    } = description;
+                    (
+                        db::NewDescription { value, langs },
+                        meanings.into_iter().map(meaning).collect(),
+                    )
+                })
+                .collect(),
+            meanings: meanings.into_iter().map(meaning).collect(),
+            pictures: pictures
+                .into_iter()
+                .map(|picture| {
+                    (
+                        Uuid::new_v4().to_hyphenated().to_string(),
+                        db::NewPicture {
+                            format: picture.format(),
+                            langs: picture.langs,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Meaning> for db::Meaning {
+    fn from(item: Meaning) -> Self {
+        let Meaning {
+            id,
+            version,
+            value,
+            langs,
+            translation_group,
+            regions,
+            register,
+            source,
+        } = item;
+        Self {
+            id,
+            version,
+            value,
+            langs,
+            translation_group,
+            regions,
+            register,
+            source: source.map(From::from),
+        }
+    }
+}
+
+impl From<Description> for db::Description {
+    fn from(item: Description) -> Self {
+        let Description {
+            id,
+            version,
+            value,
+            langs,
+            meanings,
+            translation_group,
+            source,
+        } = item;
+        Self {
+            id,
+            version,
+            value,
+            langs,
+            meanings: meanings.into_iter().map(From::from).collect(),
+            translation_group,
+            source: source.map(From::from),
+        }
+    }
+}
+
+impl From<db::Revision> for Revision {
+    fn from(item: db::Revision) -> Self {
+        let db::Revision {
+            id,
+            entity,
+            id_entity,
+            action,
+            before,
+            after,
+            username,
+            date,
+            ..
+        } = item;
+        Self {
+            id,
+            entity,
+            id_entity,
+            action,
+            before,
+            after,
+            username,
+            date,
+        }
     }
 }
 
 impl Picture {
     pub fn from(picture_db: db::Picture, storage_url: String) -> Self {
-        let db::Picture { id, langs, .. } = picture_db;
+        let db::Picture {
+            id,
+            version,
+            langs,
+            source,
+            ..
+        } = picture_db;
         Self {
             id,
+            version,
             langs,
             url: storage_url,
+            source: source.map(From::from),
+        }
+    }
+}
+
+impl From<db::Source> for Source {
+    fn from(item: db::Source) -> Self {
+        let db::Source {
+            kind,
+            page,
+            reference,
+            attribution,
+            license,
+        } = item;
+        Self {
+            kind,
+            page,
+            reference,
+            attribution,
+            license,
+        }
+    }
+}
+
+impl From<Source> for db::Source {
+    fn from(item: Source) -> Self {
+        let Source {
+            kind,
+            page,
+            reference,
+            attribution,
+            license,
+        } = item;
+        Self {
+            kind,
+            page,
+            reference,
+            attribution,
+            license,
         }
     }
 }
@@ -56,8 +379,18 @@ impl Into<db::NewDescription> for NewDescription {
 
 impl Into<db::NewMeaning> for NewMeaning {
     fn into(self) -> db::NewMeaning {
-        let Self { value, langs } = self;
-        db::NewMeaning { value, langs }
+        let Self {
+            value,
+            langs,
+            regions,
+            register,
+        } = self;
+        db::NewMeaning {
+            value,
+            langs,
+            regions,
+            register,
+        }
     }
 }
 
@@ -86,11 +419,37 @@ impl From<db::DbError> for Error {
     fn from(err: db::DbError) -> Error {
         match err {
             db::DbError::NotFound => Error::NotFound,
-            db::DbError::ForeignKeyViolation(err) => {
-                error!("{:?}", err);
-                Error::NotFound
+            db::DbError::VersionMismatch => Error::PreconditionFailed,
+            db::DbError::InvalidId(id) => Error::NotAccepted(format!("Invalid id {}", id)),
+            db::DbError::InvalidCursor(cursor) => {
+                Error::NotAccepted(format!("Invalid cursor {}", cursor))
+            }
+            db::DbError::InvalidCategory(reason) => {
+                Error::NotAccepted(format!("Invalid category, {}", reason))
+            }
+            db::DbError::InvalidRelation(reason) => {
+                Error::NotAccepted(format!("Invalid relation, {}", reason))
+            }
+            db::DbError::InvalidTag(tag) => Error::NotAccepted(format!("Invalid tag {:?}", tag)),
+            db::DbError::InvalidTranslation(reason) => {
+                Error::NotAccepted(format!("Invalid translation, {}", reason))
+            }
+            db::DbError::ForeignKeyViolation(entity) => Error::RelatedNotFound(entity),
+            db::DbError::UniqueViolation(constraint) => {
+                Error::Conflict(format!("Already exists ({})", constraint))
+            }
+            db::DbError::CheckViolation(constraint) => {
+                Error::NotAccepted(format!("Invalid data ({})", constraint))
+            }
+            db::DbError::PoolTimeout => Error::Unavailable,
+            db::DbError::Connection(err) => {
+                error!("{}", err);
+                Error::Unavailable
+            }
+            db::DbError::Mapping { column, cause } => {
+                Error::Bug(format!("Mapping {} failed: {}", column, cause))
             }
-            db::DbError::Other(err) => Error::Bug(format!("{:?}", err)),
+            db::DbError::Config(err) | db::DbError::Other(err) => Error::Bug(err),
         }
     }
 }
@@ -1,37 +1,108 @@
-use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::{Eq, PartialEq};
+use std::collections::BTreeMap;
 
 pub mod mappers;
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Gesture {
     pub id: String,
+    pub version: i32,
     pub tags: Vec<String>,
     pub descriptions: Vec<Description>,
     pub meanings: Vec<Meaning>,
     pub pictures: Vec<Picture>,
+    /// Ids of the categories the gesture belongs to, see /categories
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Gestures linked to this one, see /gestures/{id}/related/{id_related}
+    #[serde(default)]
+    pub related: Vec<RelatedGesture>,
+    /// Kinds of text a search matched: tag, description or meaning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_in: Option<Vec<String>>,
+    /// HTML of the best matching description or meaning of a search,
+    /// its text is escaped and matched words are in <mark>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Description {
     pub id: String,
+    pub version: i32,
     pub value: String,
     pub langs: Vec<String>,
     pub meanings: Vec<Meaning>,
+    /// Shared by the descriptions of the gesture saying the same thing in other langs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation_group: Option<String>,
+    /// Where it comes from, None when unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Meaning {
     pub id: String,
+    pub version: i32,
     pub value: String,
     pub langs: Vec<String>,
+    /// Shared by the meanings of the gesture saying the same thing in other langs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation_group: Option<String>,
+    /// ISO 3166-1 alpha-2 codes of the countries where it holds, empty when it holds everywhere
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// neutral, familiar, vulgar or offensive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub register: Option<String>,
+    /// Where it comes from, None when unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Picture {
     pub id: String,
+    pub version: i32,
     pub langs: Vec<String>,
     pub url: String,
+    /// Where it comes from, None when unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+}
+
+/// Where a description, meaning or picture comes from
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Source {
+    /// book, contributor or external
+    pub kind: String,
+    /// Page of the original book, only for a book source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<i32>,
+    /// Citation or url of an external reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Author or contributor to credit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+/// Snapshot of an entity before and after a modification
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Revision {
+    pub id: i64,
+    pub entity: String,
+    pub id_entity: String,
+    pub action: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub username: Option<String>,
+    pub date: NaiveDateTime,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -49,6 +120,12 @@ pub struct NewDescription {
 pub struct NewMeaning {
     pub value: String,
     pub langs: Vec<String>,
+    /// Country codes where it holds, empty when it holds everywhere
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// neutral, familiar, vulgar or offensive
+    #[serde(default)]
+    pub register: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -70,17 +147,391 @@ pub struct NewPictureFileInfo {
 fn max_default() -> u16 {
     15
 }
-fn page_default() -> u16 {
+fn page_default() -> u32 {
     1
 }
 
+/// Largest page a client can ask for
+pub const MAX_PAGE_SIZE: u16 = 100;
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct SearchParam {
     #[serde(default = "max_default")]
     pub max: u16,
     #[serde(default = "page_default")]
-    pub page: u16,
+    pub page: u32,
+    /// Keyset pagination instead of pages, empty for the first page then the `next_cursor` of the previous one
+    /// The response is then wrapped in a `GesturesPage`
+    pub cursor: Option<String>,
     pub search: Option<String>,
+    /// Lang of the searched texts, their dictionary is used for stemming and ranking
+    /// Only gestures with a text or picture in this lang are kept
+    pub lang: Option<String>,
+    /// Comma separated tags every gesture must have
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub tags: Vec<String>,
+    pub has_pictures: Option<bool>,
+    pub created_after: Option<NaiveDateTime>,
+    /// Id of a category, gestures of its subcategories are kept too
+    pub category: Option<String>,
+    /// Country code, only gestures with a meaning holding there, or everywhere, are kept
+    /// Their meanings are not narrowed, each one tells where it holds
+    pub region: Option<String>,
+    /// Only gestures with a meaning of this register are kept, in `region` when it is set too
+    pub register: Option<String>,
+    /// Count tags and langs of every matching gesture, the page is then wrapped in a `GesturesPage`
+    #[serde(default)]
+    pub facets: bool,
+    /// Relevance by default, `meaning` sorts by meanings in `lang` when it is set
+    pub sort: Option<Sort>,
+    /// Keeps the `random` order across pages, a new one on every query without it
+    pub seed: Option<String>,
+    /// Narrow the content of gestures to the langs of Accept-Language, like `lang` does
+    /// Off by default so browsers, which always send the header, get every lang to edit
+    #[serde(default)]
+    pub negotiate: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Sort {
+    Relevance,
+    Newest,
+    Oldest,
+    Updated,
+    Tag,
+    Meaning,
+    Random,
+}
+
+/// Gestures a random gesture is picked from
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct RandomParam {
+    /// Comma separated tags the gesture must have
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub tags: Vec<String>,
+    /// Only gestures with a text or picture in this lang
+    pub lang: Option<String>,
+}
+
+/// A page of gestures with the total and facets of the whole search
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct GesturesPage {
+    pub items: Vec<Gesture>,
+    pub total: i64,
+    /// Cursor of the next page, None on the last page or without keyset pagination
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Facets>,
+    /// Langs the content of the gestures was narrowed to, sent as Content-Language
+    #[serde(skip)]
+    pub content_langs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Facets {
+    pub tags: Vec<FacetCount>,
+    pub langs: Vec<FacetCount>,
+}
+
+/// Number of matching gestures having a value
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// A tag or meaning phrase proposed while typing a search
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Suggestion {
+    /// tag or meaning
+    pub kind: String,
+    pub value: String,
+    pub count: i32,
+}
+
+/// A language content can be written in, `code` is a BCP 47 tag like fr or pt-BR
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct LanguageName {
+    pub name: String,
+}
+
+/// A chapter of the book with its subcategories, siblings are ordered by position
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Category {
+    pub id: String,
+    pub position: i32,
+    /// Label by lang
+    pub labels: BTreeMap<String, String>,
+    pub children: Vec<Category>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct NewCategory {
+    /// Id of the parent category, a top level category without it
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub position: i32,
+    /// Label by lang, at least one
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Categories a gesture belongs to
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct GestureCategories {
+    pub categories: Vec<String>,
+}
+
+/// A gesture linked to another one
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct RelatedGesture {
+    pub id: String,
+    /// see_also, variant_of, has_variant or opposite_of
+    pub kind: String,
+}
+
+/// How a gesture is related to another one
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    SeeAlso,
+    /// The gesture is a variant of the related one
+    VariantOf,
+    /// The related gesture is a variant of the gesture
+    HasVariant,
+    OppositeOf,
+}
+
+/// Link to set between two gestures
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct GestureRelation {
+    pub kind: Relation,
+}
+
+/// A tag and its number of gestures
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Tag {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TagName {
+    pub name: String,
+}
+
+/// Tags replaced by `into` on every gesture
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TagsMerge {
+    pub tags: Vec<String>,
+    pub into: String,
+}
+
+/// Item an item translates, both then share a translation group
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TranslationLink {
+    /// Id of a description or meaning of the same kind and gesture
+    pub translation_of: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MissingTranslationParam {
+    /// Lang the translations are missing in
+    pub lang: String,
+    #[serde(default = "max_default")]
+    pub max: u16,
+    #[serde(default = "page_default")]
+    pub page: u32,
+}
+
+/// A description or meaning without translation in the requested lang
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MissingTranslation {
+    /// description or meaning
+    pub kind: String,
+    pub id: String,
+    pub id_gesture: String,
+    pub value: String,
+    pub langs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MissingTranslations {
+    pub items: Vec<MissingTranslation>,
+    pub total: i64,
+}
+
+fn suggest_max_default() -> u16 {
+    10
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct SuggestParam {
+    /// Start of the typed text
+    pub q: String,
+    #[serde(default = "suggest_max_default")]
+    pub max: u16,
+}
+
+/// Format of an import file, see `handlers::parse_import` for each layout
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Json,
+    Csv,
+    Yaml,
+}
+
+impl ImportFormat {
+    /// Format of a file from its extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit('.').next()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(ImportFormat::Json),
+            "csv" => Some(ImportFormat::Csv),
+            "yaml" | "yml" => Some(ImportFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ImportParam {
+    /// Guessed from the file name without it
+    pub format: Option<ImportFormat>,
+    /// Check everything, even db constraints, but keep nothing
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A gesture with its content as written in an import file
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct ImportGesture {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub descriptions: Vec<ImportDescription>,
+    /// Meanings of the gesture itself, not of one of its descriptions
+    #[serde(default)]
+    pub meanings: Vec<ImportMeaning>,
+    #[serde(default)]
+    pub pictures: Vec<ImportPicture>,
+    /// Line of the entry in the import file, found once it is parsed
+    #[serde(skip)]
+    pub line: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ImportDescription {
+    pub value: String,
+    pub langs: Vec<String>,
+    #[serde(default)]
+    pub meanings: Vec<ImportMeaning>,
+    /// Line of the entry in the import file, found once it is parsed
+    #[serde(skip)]
+    pub line: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ImportMeaning {
+    pub value: String,
+    pub langs: Vec<String>,
+    /// Line of the entry in the import file, found once it is parsed
+    #[serde(skip)]
+    pub line: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ImportPicture {
+    /// Path of the file relative to the import file, its extension gives the format
+    pub path: String,
+    pub langs: Vec<String>,
+    /// Line of the entry in the import file, found once it is parsed
+    #[serde(skip)]
+    pub line: Option<usize>,
+}
+
+impl ImportPicture {
+    /// Extension of the file
+    pub fn format(&self) -> String {
+        self.path
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+/// What an import added, or would add with `dry_run`
+/// Nothing is added when there are errors
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub gestures: usize,
+    pub descriptions: usize,
+    pub meanings: usize,
+    pub pictures: usize,
+    /// Ids of the added gestures, in the order of the file
+    pub ids: Vec<String>,
+    pub errors: Vec<ImportError>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ImportError {
+    /// `line 12` of a CSV file or of a syntax error, line and path of the entry otherwise like
+    /// `line 12, gestures[2].meanings[0]`
+    pub location: String,
+    pub message: String,
+}
+
+/// Every gesture with the languages and category tree, and the picture files to save beside them
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct GesturesExport {
+    pub languages: Vec<Language>,
+    pub categories: Vec<Category>,
+    pub gestures: Vec<Gesture>,
+    /// Creation date of every gesture, description, meaning and picture by id, it orders them
+    #[serde(default)]
+    pub creation_dates: BTreeMap<String, NaiveDateTime>,
+    pub pictures: Vec<PictureFile>,
+}
+
+/// A picture file of an export archive, named `pictures/<id>.<format>`
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct PictureFile {
+    pub id: String,
+    pub format: String,
+}
+
+impl PictureFile {
+    pub fn path(&self) -> String {
+        format!("pictures/{}.{}", self.id, self.format)
+    }
+
+    pub fn from_path(path: &str) -> Option<Self> {
+        let name = path.strip_prefix("pictures/")?;
+        let (id, format) = name.split_at(name.rfind('.')?);
+        Some(Self {
+            id: id.to_owned(),
+            format: format[1..].to_owned(),
+        })
+    }
+}
+
+fn comma_separated<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Ok(value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect())
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -89,4 +540,11 @@ pub enum Error {
     NotFound,
     Auth,
     NotAccepted(String),
+    PreconditionRequired,
+    PreconditionFailed,
+    /// The entity (gesture, description...) a new item is attached to does not exist
+    RelatedNotFound(String),
+    Conflict(String),
+    /// Db is overloaded or unreachable, retry later
+    Unavailable,
 }
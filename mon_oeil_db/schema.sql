@@ -3,17 +3,38 @@ DROP TABLE IF EXISTS descriptions CASCADE;
 DROP TABLE IF EXISTS meanings CASCADE;
 DROP TABLE IF EXISTS pictures CASCADE;
 DROP TABLE IF EXISTS users CASCADE;
+DROP TABLE IF EXISTS history CASCADE;
+DROP TABLE IF EXISTS suggestions CASCADE;
+DROP TABLE IF EXISTS languages CASCADE;
+DROP TABLE IF EXISTS categories CASCADE;
+DROP TABLE IF EXISTS category_labels CASCADE;
+DROP TABLE IF EXISTS gesture_categories CASCADE;
+DROP TABLE IF EXISTS gesture_links CASCADE;
+DROP TABLE IF EXISTS search_documents CASCADE;
 
 CREATE TABLE gestures (
-	id_gesture 	UUID PRIMARY KEY,
-    tags		text[]
+	id_gesture 		UUID PRIMARY KEY,
+    tags			text[] NOT NULL,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW(),
+	version			INTEGER NOT NULL DEFAULT 1,
+    document 		tsvector
 );
 
 CREATE TABLE descriptions (
 	id_description 	UUID PRIMARY KEY,
 	id_gesture		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
     val				text NOT NULL,
-    langs			text[]
+    langs			text[] NOT NULL,
+	-- descriptions saying the same thing in other langs share it
+	translation_group	UUID,
+	-- the book at source_page, a contributor or an external reference
+	source			text CHECK (source IN ('book', 'contributor', 'external')),
+	source_page		INTEGER CHECK (source_page IS NULL OR (source_page > 0 AND source IS NOT DISTINCT FROM 'book')),
+	source_reference	text,
+	attribution		text,
+	license			text,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW(),
+	version			INTEGER NOT NULL DEFAULT 1
 );
 
 CREATE TABLE meanings (
@@ -21,23 +42,484 @@ CREATE TABLE meanings (
     id_description 	UUID REFERENCES descriptions ON DELETE CASCADE,
    	id_gesture 		UUID REFERENCES gestures ON DELETE CASCADE,
     val				text NOT NULL,
-    langs			text[]
+    langs			text[] NOT NULL,
+	-- meanings saying the same thing in other langs share it
+	translation_group	UUID,
+	-- ISO 3166-1 alpha-2 codes of the countries where it holds, empty when it holds everywhere
+	regions			text[] NOT NULL DEFAULT '{}',
+	register		text CHECK (register IN ('neutral', 'familiar', 'vulgar', 'offensive')),
+	-- the book at source_page, a contributor or an external reference
+	source			text CHECK (source IN ('book', 'contributor', 'external')),
+	source_page		INTEGER CHECK (source_page IS NULL OR (source_page > 0 AND source IS NOT DISTINCT FROM 'book')),
+	source_reference	text,
+	attribution		text,
+	license			text,
+    creation_date	TIMESTAMP NOT NULL DEFAULT NOW(),
+	version			INTEGER NOT NULL DEFAULT 1,
     CHECK (id_description IS NULL OR id_gesture IS NULL)
 );
 
+CREATE INDEX meanings_regions_index
+	ON meanings USING GIN (regions);
+
+CREATE INDEX descriptions_translation_group_index
+	ON descriptions (translation_group);
+
+CREATE INDEX meanings_translation_group_index
+	ON meanings (translation_group);
+
 CREATE TABLE pictures (
-	id_picture 	UUID PRIMARY KEY,
-	id_gesture 	UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
-    langs		text[]
+	id_picture 		UUID PRIMARY KEY,
+	id_gesture 		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
+	langs			text[] NOT NULL,
+    format			text NOT NULL,
+	-- the book at source_page, a contributor or an external reference
+	source			text CHECK (source IN ('book', 'contributor', 'external')),
+	source_page		INTEGER CHECK (source_page IS NULL OR (source_page > 0 AND source IS NOT DISTINCT FROM 'book')),
+	source_reference	text,
+	attribution		text,
+	license			text,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW(),
+	version			INTEGER NOT NULL DEFAULT 1
 );
 
 CREATE TABLE users
 (
-    username       text PRIMARY KEY,
-    PASSWORD    text
+    username    	text PRIMARY KEY,
+    PASSWORD    	text NOT NULL,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE history
+(
+    id_history		BIGSERIAL PRIMARY KEY,
+    entity			text NOT NULL,
+    id_entity		UUID NOT NULL,
+    id_gesture		UUID,
+    action			text NOT NULL,
+    before			jsonb,
+    after			jsonb,
+    username		text,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW()
 );
 
+CREATE INDEX history_id_gesture_index
+	ON history (id_gesture);
+
+-- tags and meaning phrases proposed while typing a search, kept up to date by triggers
+CREATE TABLE suggestions
+(
+    kind			text NOT NULL,
+    term			text NOT NULL,
+    normalized		text NOT NULL,
+    count			INTEGER NOT NULL,
+    PRIMARY KEY (kind, term)
+);
+
+-- languages content can be written in, BCP 47 tags: a language subtag then script, region or variant subtags
+CREATE TABLE languages
+(
+    code			text PRIMARY KEY CHECK (code ~ '^[a-z]{2,3}(-[A-Za-z]{4})?(-([A-Z]{2}|[0-9]{3}))?(-([A-Za-z0-9]{5,8}|[0-9][A-Za-z0-9]{3}))*$'),
+    name			text NOT NULL CHECK (name <> ''),
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- en-us and en-US are the same language
+CREATE UNIQUE INDEX languages_code_case_index
+	ON languages (lower(code));
+
+INSERT INTO languages (code, name) VALUES
+	('fr', 'Français'),
+	('en', 'English'),
+	('en-GB', 'English (United Kingdom)'),
+	('en-US', 'English (United States)'),
+	('de', 'Deutsch'),
+	('es', 'Español'),
+	('it', 'Italiano'),
+	('pt', 'Português'),
+	('nl', 'Nederlands'),
+	('ru', 'Русский');
+
+-- chapters of the book, a tree where siblings are ordered by position
+-- removing a category removes its subcategories
+CREATE TABLE categories
+(
+    id_category		UUID PRIMARY KEY,
+    id_parent		UUID REFERENCES categories ON DELETE CASCADE CHECK (id_parent <> id_category),
+    position		INTEGER NOT NULL DEFAULT 0,
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX categories_id_parent_index
+	ON categories (id_parent);
+
+-- name of a category in a lang
+CREATE TABLE category_labels
+(
+    id_category		UUID REFERENCES categories ON DELETE CASCADE NOT NULL,
+    lang			text NOT NULL,
+    label			text NOT NULL CHECK (label <> ''),
+    PRIMARY KEY (id_category, lang)
+);
+
+CREATE TABLE gesture_categories
+(
+    id_gesture		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
+    id_category		UUID REFERENCES categories ON DELETE CASCADE NOT NULL,
+    PRIMARY KEY (id_gesture, id_category)
+);
+
+CREATE INDEX gesture_categories_id_category_index
+	ON gesture_categories (id_category);
+
+-- typed cross-references between gestures, one per pair of gestures
+-- see_also and opposite_of read both ways, variant_of goes from the variant to the gesture it varies
+CREATE TABLE gesture_links
+(
+    id_gesture		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
+    id_related		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
+    kind			text NOT NULL CHECK (kind IN ('see_also', 'variant_of', 'opposite_of')),
+	creation_date	TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id_gesture, id_related),
+    CHECK (id_gesture <> id_related)
+);
+
+CREATE UNIQUE INDEX gesture_links_pair_index
+	ON gesture_links (LEAST(id_gesture, id_related), GREATEST(id_gesture, id_related));
+
+CREATE INDEX gesture_links_id_related_index
+	ON gesture_links (id_related);
+
+-- one row per description or meaning and language, kept up to date by triggers
+-- kind tells where the text comes from: description or meaning, id_text is its id
+-- a text without language has a NULL lang, normalized is the text compared by trigram similarity
+CREATE TABLE search_documents
+(
+    id_gesture		UUID REFERENCES gestures ON DELETE CASCADE NOT NULL,
+    id_text			UUID NOT NULL,
+    kind			text NOT NULL,
+    lang			text,
+    val				text NOT NULL,
+    normalized		text NOT NULL,
+    document		tsvector NOT NULL
+);
+
+CREATE INDEX search_documents_document_index
+	ON search_documents
+	USING GIN (document);
+
+CREATE INDEX search_documents_id_text_index
+	ON search_documents (id_text);
+
+CREATE INDEX search_documents_id_gesture_index
+	ON search_documents (id_gesture);
+
+-- a category and every category below it
+CREATE OR REPLACE FUNCTION category_subtree(root UUID) RETURNS SETOF UUID AS $$
+	WITH RECURSIVE subtree AS (
+		SELECT id_category FROM categories WHERE id_category = root
+		UNION
+		SELECT c.id_category FROM categories AS c JOIN subtree AS s ON c.id_parent = s.id_category
+	)
+	SELECT id_category FROM subtree;
+$$ LANGUAGE SQL STABLE;
+
+------- SEARCH CONFIG     -------
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- lowercase text without accents, so geste, gèste and GESTE are the same
+CREATE OR REPLACE FUNCTION search_text(val text) RETURNS text AS $$
+	SELECT lower(unaccent('unaccent', val))
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+-- tags of a gesture as one normalized text
+CREATE OR REPLACE FUNCTION tags_text(tags text[]) RETURNS text AS $$
+	SELECT search_text(array_to_string(tags, ' '))
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+-- close texts are found with the <% operator, the threshold is set by the connections
+CREATE INDEX gesture_tags_trgm_index
+	ON gestures
+	USING GIN (tags_text(tags) gin_trgm_ops);
+
+CREATE INDEX search_documents_trgm_index
+	ON search_documents
+	USING GIN (normalized gin_trgm_ops);
+
+-- copies of the text search configurations that drop accents before stemming,
+-- so documents, queries and highlighted snippets of accented texts see the same words
+DO $$
+	DECLARE
+		config text;
+	BEGIN
+		FOREACH config IN ARRAY ARRAY['simple', 'french', 'english', 'german', 'spanish', 'italian', 'portuguese', 'dutch', 'russian'] LOOP
+			EXECUTE format('DROP TEXT SEARCH CONFIGURATION IF EXISTS %I CASCADE', 'unaccent_' || config);
+			EXECUTE format('CREATE TEXT SEARCH CONFIGURATION %I (COPY = %I)', 'unaccent_' || config, config);
+			EXECUTE format('ALTER TEXT SEARCH CONFIGURATION %I ALTER MAPPING FOR hword, hword_part, word WITH unaccent, %I',
+				'unaccent_' || config, CASE config WHEN 'simple' THEN 'simple' ELSE config || '_stem' END);
+		END LOOP;
+	END
+$$;
+
+-- text search configuration of a lang, simple (no stemming) when unknown, pt-BR uses the one of pt
+CREATE OR REPLACE FUNCTION lang_config(lang text) RETURNS regconfig AS $$
+	SELECT (CASE split_part(lower(lang), '-', 1)
+		WHEN 'fr' THEN 'unaccent_french'
+		WHEN 'en' THEN 'unaccent_english'
+		WHEN 'us' THEN 'unaccent_english'
+		WHEN 'gb' THEN 'unaccent_english'
+		WHEN 'de' THEN 'unaccent_german'
+		WHEN 'es' THEN 'unaccent_spanish'
+		WHEN 'it' THEN 'unaccent_italian'
+		WHEN 'pt' THEN 'unaccent_portuguese'
+		WHEN 'nl' THEN 'unaccent_dutch'
+		WHEN 'ru' THEN 'unaccent_russian'
+		ELSE 'unaccent_simple'
+	END)::regconfig
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- text safe to put in HTML
+CREATE OR REPLACE FUNCTION html_escape(val text) RETURNS text AS $$
+	SELECT replace(replace(replace(val, '&', '&amp;'), '<', '&lt;'), '>', '&gt;')
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+DROP AGGREGATE IF EXISTS tsquery_any(tsquery);
+
+CREATE AGGREGATE tsquery_any(tsquery) (SFUNC = tsquery_or, STYPE = tsquery);
+
+-- a search in the dictionary of every lang, it finds with the GIN indexes every document
+-- the query of its own lang could match, those are then checked with that query
+CREATE OR REPLACE FUNCTION any_lang_tsquery(query text) RETURNS tsquery AS $$
+	SELECT tsquery_any(DISTINCT to_tsquery(c.config, search_text(query)))
+	FROM (SELECT lang_config(code) FROM languages UNION SELECT lang_config(NULL)) as c(config)
+$$ LANGUAGE SQL STABLE STRICT;
+
+------- VIEWS             -------
+
 CREATE VIEW meanings_with_gesture_id AS
     SELECT meanings.*, descriptions.id_gesture as id_description_gesture
     FROM meanings
-	LEFT JOIN descriptions ON (meanings.id_description = descriptions.id_description);
\ No newline at end of file
+	LEFT JOIN descriptions ON (meanings.id_description = descriptions.id_description);
+
+-- one row per gesture and lang of its descriptions, meanings and pictures
+CREATE VIEW gesture_langs as
+	SELECT d.id_gesture, l.lang FROM descriptions as d, unnest(d.langs) as l(lang)
+	UNION
+	SELECT COALESCE(m.id_description_gesture, m.id_gesture) as id_gesture, l.lang FROM meanings_with_gesture_id as m, unnest(m.langs) as l(lang)
+	UNION
+	SELECT p.id_gesture, l.lang FROM pictures as p, unnest(p.langs) as l(lang);
+
+-- one row per searchable text and language, tags have no language
+-- kind tells where the text comes from: tag, description or meaning
+CREATE VIEW searchable as
+	SELECT g.id_gesture, NULL as lang, g.document, 'tag' as kind, array_to_string(g.tags, ' ') as val FROM gestures as g
+	UNION ALL
+	SELECT s.id_gesture, s.lang, s.document, s.kind, s.val FROM search_documents as s;
+
+-- one row per text and language compared by trigram similarity when the full text search finds few gestures
+CREATE VIEW fuzzy_searchable as
+	SELECT g.id_gesture, NULL as lang, tags_text(g.tags) as normalized, 'tag' as kind FROM gestures as g
+	UNION ALL
+	SELECT s.id_gesture, s.lang, s.normalized, s.kind FROM search_documents as s;
+
+-- one row per description and meaning, with the gesture they belong to, to find what needs a translation
+CREATE VIEW translatable as
+	SELECT 'description' as kind, d.id_description as id, d.id_gesture, d.val, d.langs, d.translation_group FROM descriptions as d
+	UNION ALL
+	SELECT 'meaning', m.id_meaning, COALESCE(m.id_description_gesture, m.id_gesture), m.val, m.langs, m.translation_group FROM meanings_with_gesture_id as m;
+
+-- links of a gesture from both ends, a variant_of link read from its target is has_variant
+CREATE VIEW related_gestures as
+	SELECT l.id_gesture, l.id_related, l.kind, l.creation_date FROM gesture_links as l
+	UNION ALL
+	SELECT l.id_related, l.id_gesture, CASE l.kind WHEN 'variant_of' THEN 'has_variant' ELSE l.kind END, l.creation_date FROM gesture_links as l;
+
+------- RESEARCH TRIGGERS -------
+------- Gestures          -------
+
+DROP FUNCTION IF EXISTS gestures_document_trigger();
+
+CREATE FUNCTION gestures_document_trigger() RETURNS trigger AS $$
+	BEGIN
+		new.document := array_to_tsvector(ARRAY(SELECT search_text(tag) FROM unnest(new.tags) as tag));
+		return new;
+	END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER  gestures_document_update BEFORE INSERT OR UPDATE
+	ON gestures FOR EACH ROW EXECUTE PROCEDURE gestures_document_trigger();
+
+CREATE INDEX gesture_document_index
+	ON gestures
+	USING GIN (document);
+
+------- Descriptions and meanings -------
+
+-- replace the documents of a text by one per lang, stemmed with the dictionary of the lang
+CREATE OR REPLACE FUNCTION index_search_text(gesture UUID, text_id UUID, text_kind text, text_langs text[], text_val text) RETURNS void AS $$
+	DELETE FROM search_documents WHERE id_text = text_id;
+	INSERT INTO search_documents (id_gesture, id_text, kind, lang, val, normalized, document)
+		SELECT gesture, text_id, text_kind, l.lang, text_val, search_text(text_val), to_tsvector(lang_config(l.lang), text_val)
+		FROM unnest(CASE WHEN text_langs = '{}' THEN '{NULL}' ELSE text_langs END) as l(lang);
+$$ LANGUAGE SQL;
+
+DROP FUNCTION IF EXISTS descriptions_document_trigger();
+
+CREATE FUNCTION descriptions_document_trigger() RETURNS trigger AS $$
+	BEGIN
+		IF TG_OP = 'DELETE' THEN
+			DELETE FROM search_documents WHERE id_text = old.id_description;
+		ELSE
+			PERFORM index_search_text(new.id_gesture, new.id_description, 'description', new.langs, new.val);
+		END IF;
+		return NULL;
+	END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER  descriptions_document_update AFTER INSERT OR UPDATE OF val, langs OR DELETE
+	ON descriptions FOR EACH ROW EXECUTE PROCEDURE descriptions_document_trigger();
+
+DROP FUNCTION IF EXISTS meanings_document_trigger();
+
+CREATE FUNCTION meanings_document_trigger() RETURNS trigger AS $$
+	DECLARE
+		gesture		UUID;
+	BEGIN
+		IF TG_OP = 'DELETE' THEN
+			DELETE FROM search_documents WHERE id_text = old.id_meaning;
+		ELSE
+			gesture := COALESCE(new.id_gesture,
+				(SELECT d.id_gesture FROM descriptions as d WHERE d.id_description = new.id_description));
+			PERFORM index_search_text(gesture, new.id_meaning, 'meaning', new.langs, new.val);
+		END IF;
+		return NULL;
+	END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER  meanings_document_update AFTER INSERT OR UPDATE OF val, langs OR DELETE
+	ON meanings FOR EACH ROW EXECUTE PROCEDURE meanings_document_trigger();
+	
+------- SUGGESTIONS TRIGGERS -------
+
+CREATE INDEX suggestions_prefix_index
+	ON suggestions (normalized text_pattern_ops);
+
+-- add delta to the count of every term, terms nobody uses anymore are removed
+CREATE OR REPLACE FUNCTION suggestions_count(suggestion_kind text, terms text[], delta integer) RETURNS void AS $$
+	INSERT INTO suggestions (kind, term, normalized, count)
+		SELECT DISTINCT suggestion_kind, t.term, search_text(t.term), delta FROM unnest(terms) as t(term)
+	ON CONFLICT (kind, term) DO UPDATE SET count = suggestions.count + EXCLUDED.count;
+	DELETE FROM suggestions WHERE kind = suggestion_kind AND count <= 0;
+$$ LANGUAGE SQL;
+
+DROP FUNCTION IF EXISTS gestures_suggestions_trigger();
+
+CREATE FUNCTION gestures_suggestions_trigger() RETURNS trigger AS $$
+	BEGIN
+		IF TG_OP <> 'INSERT' THEN
+			PERFORM suggestions_count('tag', old.tags, -1);
+		END IF;
+		IF TG_OP <> 'DELETE' THEN
+			PERFORM suggestions_count('tag', new.tags, 1);
+		END IF;
+		return NULL;
+	END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER  gestures_suggestions AFTER INSERT OR UPDATE OR DELETE
+	ON gestures FOR EACH ROW EXECUTE PROCEDURE gestures_suggestions_trigger();
+
+DROP FUNCTION IF EXISTS meanings_suggestions_trigger();
+
+CREATE FUNCTION meanings_suggestions_trigger() RETURNS trigger AS $$
+	BEGIN
+		IF TG_OP <> 'INSERT' THEN
+			PERFORM suggestions_count('meaning', ARRAY[old.val], -1);
+		END IF;
+		IF TG_OP <> 'DELETE' THEN
+			PERFORM suggestions_count('meaning', ARRAY[new.val], 1);
+		END IF;
+		return NULL;
+	END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER  meanings_suggestions AFTER INSERT OR UPDATE OR DELETE
+	ON meanings FOR EACH ROW EXECUTE PROCEDURE meanings_suggestions_trigger();
+
+------- HISTORY TRIGGERS  -------
+
+DROP FUNCTION IF EXISTS history_trigger();
+
+-- TG_ARGV[0] is the id column of the audited table
+-- the author is read from the transaction setting mon_oeil.username
+CREATE FUNCTION history_trigger() RETURNS trigger AS $$
+	DECLARE
+		before_row	jsonb;
+		after_row	jsonb;
+		current_row	jsonb;
+		gesture		UUID;
+	BEGIN
+		IF TG_OP <> 'INSERT' THEN
+			before_row := to_jsonb(old) - 'document';
+		END IF;
+		IF TG_OP <> 'DELETE' THEN
+			after_row := to_jsonb(new) - 'document';
+		END IF;
+		IF before_row = after_row THEN
+			return new;
+		END IF;
+		current_row := COALESCE(after_row, before_row);
+
+		gesture := (current_row ->> 'id_gesture')::UUID;
+		IF gesture IS NULL THEN
+			-- meaning of a description, the description may already be deleted by cascade
+			SELECT d.id_gesture INTO gesture FROM descriptions as d
+				WHERE d.id_description = (current_row ->> 'id_description')::UUID;
+		END IF;
+		IF gesture IS NULL THEN
+			SELECT h.id_gesture INTO gesture FROM history as h
+				WHERE h.id_entity = (current_row ->> 'id_description')::UUID AND h.id_gesture IS NOT NULL
+				LIMIT 1;
+		END IF;
+
+		INSERT INTO history (entity, id_entity, id_gesture, action, before, after, username)
+			VALUES (
+				TG_TABLE_NAME,
+				(current_row ->> TG_ARGV[0])::UUID,
+				gesture,
+				lower(TG_OP),
+				before_row,
+				after_row,
+				NULLIF(current_setting('mon_oeil.username', true), '')
+			);
+
+		IF TG_OP = 'DELETE' THEN
+			return old;
+		END IF;
+		return new;
+	END
+$$ LANGUAGE plpgsql;
+
+-- deletions are recorded BEFORE so cascaded rows can still find their gesture
+
+CREATE TRIGGER  gestures_history_update AFTER INSERT OR UPDATE
+	ON gestures FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_gesture');
+CREATE TRIGGER  gestures_history_delete BEFORE DELETE
+	ON gestures FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_gesture');
+
+CREATE TRIGGER  descriptions_history_update AFTER INSERT OR UPDATE
+	ON descriptions FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_description');
+CREATE TRIGGER  descriptions_history_delete BEFORE DELETE
+	ON descriptions FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_description');
+
+CREATE TRIGGER  meanings_history_update AFTER INSERT OR UPDATE
+	ON meanings FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_meaning');
+CREATE TRIGGER  meanings_history_delete BEFORE DELETE
+	ON meanings FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_meaning');
+
+CREATE TRIGGER  pictures_history_update AFTER INSERT OR UPDATE
+	ON pictures FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_picture');
+CREATE TRIGGER  pictures_history_delete BEFORE DELETE
+	ON pictures FOR EACH ROW EXECUTE PROCEDURE history_trigger('id_picture');
\ No newline at end of file
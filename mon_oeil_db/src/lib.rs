@@ -1,162 +1,387 @@
-use std::env;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-use deadpool_postgres::{Client, Config, ManagerConfig, Pool, RecyclingMethod};
+use chrono::{Duration, NaiveDateTime};
+use deadpool_postgres::{
+    Client, Manager, ManagerConfig, Pool, PoolError, RecyclingMethod, Transaction,
+};
 use futures::future;
 use linked_hash_map::LinkedHashMap;
+use postgres_native_tls::MakeTlsConnector;
 use tokio_pg_mapper::FromTokioPostgresRow;
-use tokio_postgres::{error::SqlState, types::ToSql, Error, NoTls};
+use tokio_postgres::{
+    error::{DbError as PgDbError, SqlState},
+    types::ToSql,
+    Column, Error, NoTls, Row, Statement,
+};
 use uuid::Uuid;
 
+mod config;
 mod models;
+mod query;
+mod search;
+mod tags;
+
+pub use config::DbConfig;
+pub use tags::{normalize_tag, normalize_tags};
 
 use models::raw::*;
 pub use models::*;
+use query::Params;
 
-pub fn connect_db() -> GestureClientPool {
-    let (host, port, user, password, dbname) = (
-        env::var("PG_HOST").unwrap(),
-        env::var("PG_PORT").unwrap(),
-        env::var("PG_USER").unwrap(),
-        env::var("PG_PWD").unwrap(),
-        env::var("PG_DB_NAME").unwrap(),
-    );
-
-    GestureClientPool::connect(&host, &port, &user, &password, &dbname).unwrap()
+/// Create the db pool from env vars, see `DbConfig::from_env`
+pub fn connect_db() -> Result<GestureClientPool, DbError> {
+    GestureClientPool::connect(DbConfig::from_env()?)
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum DbError {
+    /// The id is not a valid uuid
+    InvalidId(String),
+    /// The pagination cursor was not given by a previous page
+    InvalidCursor(String),
+    /// The referenced entity (gesture, description...) does not exist
     ForeignKeyViolation(String),
+    /// Name of the violated unique constraint
+    UniqueViolation(String),
+    /// Name of the violated check constraint
+    CheckViolation(String),
+    /// Why a category can't be placed there
+    InvalidCategory(String),
+    /// Why two gestures can't be linked
+    InvalidRelation(String),
+    /// A tag can't be empty once normalized
+    InvalidTag(String),
+    /// Why an item can't be the translation of another
+    InvalidTranslation(String),
     NotFound,
+    VersionMismatch,
+    /// No connection available in the pool in time
+    PoolTimeout,
+    /// The db can't be reached
+    Connection(String),
+    /// Invalid or missing settings
+    Config(String),
+    /// A row can't be decoded, the db schema doesn't match the models
+    Mapping {
+        column: String,
+        cause: String,
+    },
     Other(String),
 }
 
 impl From<Error> for DbError {
     fn from(err: Error) -> DbError {
-        match err.code().map(SqlState::code) {
-            Some(x) if x == SqlState::FOREIGN_KEY_VIOLATION.code() => {
-                DbError::ForeignKeyViolation(format!("{:?}", err))
+        let db_err = std::error::Error::source(&err).and_then(|e| e.downcast_ref::<PgDbError>());
+
+        match (err.code(), db_err) {
+            (Some(code), Some(db_err)) if *code == SqlState::FOREIGN_KEY_VIOLATION => {
+                DbError::ForeignKeyViolation(referenced_entity(db_err))
+            }
+            (Some(code), Some(db_err)) if *code == SqlState::UNIQUE_VIOLATION => {
+                DbError::UniqueViolation(db_err.constraint().unwrap_or_default().to_owned())
+            }
+            (Some(code), Some(db_err)) if *code == SqlState::CHECK_VIOLATION => {
+                DbError::CheckViolation(db_err.constraint().unwrap_or_default().to_owned())
             }
+            _ if err.is_closed() => DbError::Connection(format!("{}", err)),
             _ => DbError::Other(format!("{:?}", err)),
         }
     }
 }
 
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::InvalidId(id) => write!(f, "invalid id {}", id),
+            DbError::InvalidCursor(cursor) => write!(f, "invalid cursor {}", cursor),
+            DbError::ForeignKeyViolation(entity) => write!(f, "{} not found", entity),
+            DbError::UniqueViolation(constraint) => write!(f, "{} violated", constraint),
+            DbError::CheckViolation(constraint) => write!(f, "{} violated", constraint),
+            DbError::InvalidCategory(reason) => write!(f, "invalid category: {}", reason),
+            DbError::InvalidRelation(reason) => write!(f, "invalid relation: {}", reason),
+            DbError::InvalidTag(tag) => write!(f, "invalid tag: {:?}", tag),
+            DbError::InvalidTranslation(reason) => write!(f, "invalid translation: {}", reason),
+            DbError::NotFound => write!(f, "not found"),
+            DbError::VersionMismatch => write!(f, "version mismatch"),
+            DbError::PoolTimeout => write!(f, "timeout waiting for a db connection"),
+            DbError::Connection(e) => write!(f, "db connection failed: {}", e),
+            DbError::Config(e) => write!(f, "db config: {}", e),
+            DbError::Mapping { column, cause } => write!(f, "mapping {}: {}", column, cause),
+            DbError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<PoolError> for DbError {
+    fn from(err: PoolError) -> DbError {
+        match err {
+            PoolError::Timeout(_) => DbError::PoolTimeout,
+            err => DbError::Connection(format!("{}", err)),
+        }
+    }
+}
+
+/// Entity missing for a foreign key violation
+/// The detail looks like `Key (id_gesture)=(...) is not present in table "gestures".`
+fn referenced_entity(db_err: &PgDbError) -> String {
+    let table = db_err
+        .detail()
+        .and_then(|detail| detail.rsplit('"').nth(1))
+        .or_else(|| db_err.table())
+        .unwrap_or_default();
+
+    match table {
+        G_TABLE => "gesture",
+        D_TABLE => "description",
+        M_TABLE => "meaning",
+        P_TABLE => "picture",
+        C_TABLE => "category",
+        table => table,
+    }
+    .to_owned()
+}
+
+pub(crate) fn parse_id(id: &str) -> Result<Uuid, DbError> {
+    Uuid::parse_str(id).map_err(|_| DbError::InvalidId(id.to_owned()))
+}
+
 #[derive(Clone)]
 pub struct GestureClientPool(Pool);
 
 impl GestureClientPool {
-    /// Create db pool
-    pub fn connect(
-        host: &str,
-        port: &str,
-        user: &str,
-        password: &str,
-        dbname: &str,
-    ) -> Result<Self, DbError> {
-        let mut cfg = Config::new();
-        cfg.host = Some(host.to_owned());
-        cfg.port = Some(
-            port.to_owned()
-                .parse()
-                .map_err(|e| DbError::Other(format!("Config port err : {:?}", e)))?,
-        );
-        cfg.user = Some(user.to_owned());
-        cfg.password = Some(password.to_owned());
-        cfg.dbname = Some(dbname.to_owned());
-        cfg.manager = Some(ManagerConfig {
+    /// Create db pool, connections are opened lazily
+    pub fn connect(config: DbConfig) -> Result<Self, DbError> {
+        let manager_config = ManagerConfig {
             recycling_method: RecyclingMethod::Fast,
-        });
+        };
+        let mut pg = config.pg;
+        config::add_option(
+            &mut pg,
+            &format!(
+                "-c pg_trgm.word_similarity_threshold={}",
+                search::FUZZY_MIN_SIMILARITY
+            ),
+        );
 
-        let pool = cfg
-            .create_pool(NoTls)
-            .map_err(|e| DbError::Other(format!("Conf pool err : {:?}", e)))?;
+        let pool = if config.tls {
+            let mut builder = native_tls::TlsConnector::builder();
+            if let Some(pem) = &config.ca_cert {
+                let ca = native_tls::Certificate::from_pem(pem)
+                    .map_err(|e| DbError::Config(format!("PG_CA_CERT is invalid: {}", e)))?;
+                builder.add_root_certificate(ca);
+            }
+            let connector = builder
+                .build()
+                .map_err(|e| DbError::Config(format!("TLS setup failed: {}", e)))?;
+            let manager =
+                Manager::from_config(pg, MakeTlsConnector::new(connector), manager_config);
+            Pool::from_config(manager, config.pool)
+        } else {
+            let manager = Manager::from_config(pg, NoTls, manager_config);
+            Pool::from_config(manager, config.pool)
+        };
 
         Ok(Self(pool))
     }
 
+    /// Check every table and column used by the models exists with a decodable type
+    /// Meant to be run at startup to fail fast on schema drift
+    pub async fn check_schema(&self) -> Result<(), DbError> {
+        let client = self.get().await?;
+
+        client.check_table::<RawGesture>().await?;
+        client.check_table::<RawDescription>().await?;
+        client.check_table::<RawMeaning>().await?;
+        client.check_table::<RawPicture>().await?;
+        client.check_table::<RawUser>().await?;
+        client.check_table::<RawRevision>().await?;
+        client.check_table::<RawSuggestion>().await?;
+        client.check_table::<RawLanguage>().await?;
+        client.check_table::<RawTranslatable>().await?;
+        client.check_table::<RawCategory>().await?;
+        client.check_table::<RawCategoryLabel>().await?;
+        client.check_table::<RawGestureCategory>().await?;
+        client.check_table::<RawGestureLink>().await?;
+        client.check_table::<RawRelatedGesture>().await?;
+
+        for (table, names) in UNMAPPED_COLUMNS {
+            let statement = client.table_statement(table).await?;
+            if let Some(name) = names
+                .iter()
+                .find(|name| !statement.columns().iter().any(|c| c.name() == **name))
+            {
+                return Err(DbError::Mapping {
+                    column: format!("{}.{}", table, name),
+                    cause: "missing column".to_owned(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Borow a client from the pool
     /// Future wait until one is available
     /// On drop return it to the pool
     pub async fn get(&self) -> Result<GestureClient, DbError> {
-        let client: Client = self
-            .0
-            .get()
-            .await
-            .map_err(|e| DbError::Other(format!("Getting pool failed {:?} {}", e, e)))?;
+        let client: Client = self.0.get().await?;
 
-        Ok(GestureClient { client })
+        Ok(GestureClient {
+            client,
+            author: None,
+        })
     }
 }
 
 pub struct GestureClient {
     client: Client,
+    author: Option<String>,
 }
 
 impl GestureClient {
-    /// Retrieve all gestures from db
+    /// Set the user responsible for the next modifications, it is recorded in history
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.author = author;
+    }
+
+    /// Begin a transaction in which history triggers can read the author
+    async fn transaction(&mut self) -> Result<Transaction<'_>, DbError> {
+        let author = self.author.as_deref().unwrap_or("");
+        let transaction = self.client.transaction().await?;
+        transaction
+            .execute(
+                format!("SELECT set_config('{}', $1, true)", AUTHOR_SETTING).as_str(),
+                &[&author],
+            )
+            .await?;
+        Ok(transaction)
+    }
+
+    async fn check_table<T: Columns>(&self) -> Result<(), DbError> {
+        let statement = self.table_statement(T::TABLE).await?;
+        check_columns::<T>(statement.columns())
+    }
+
+    /// Statement selecting every column of a table, to inspect them
+    async fn table_statement(&self, table: &str) -> Result<Statement, DbError> {
+        // bypass the pool statement cache, the schema may have changed since
+        let client: &tokio_postgres::Client = &self.client;
+        client
+            .prepare(format!("SELECT * FROM {} LIMIT 0", table).as_str())
+            .await
+            .map_err(|e| DbError::Mapping {
+                column: table.to_owned(),
+                cause: format!("{}", e),
+            })
+    }
+
+    /// Retrieve a page of gestures from db
+    /// With `facets`, tags and langs are counted over every matching gesture, not only the page
+    /// Keyset pagination always lists the newest first, `sort` only applies to pages
     pub async fn all_gestures(
         &self,
         pagination: PaginationRequest,
-        search: Option<String>,
-    ) -> Result<(Vec<Gesture>, u16), DbError> {
+        search: Option<SearchRequest>,
+        filter: FilterRequest,
+        sort: Sort,
+        facets: bool,
+    ) -> Result<GesturesPage, DbError> {
         let client = &self.client;
 
-        let offset = (pagination.page - 1) * pagination.max;
+        // the count and facets cover every page, they get their own params without the cursor
+        let mut count_params = Params::default();
+        let (from, count_conditions, _) =
+            gestures_selection(search.clone(), filter.clone(), &mut count_params)?;
+        let count_where_clause = query::where_clause(&count_conditions);
 
-        let search = search.map(|s| format!("{}:*", s));
+        let mut params = Params::default();
+        let (_, mut conditions, searching) = gestures_selection(search, filter, &mut params)?;
 
-        let (gestures, gestures_count_query) = match search.clone() {
-            Some(search) => {
-                let gestures_query = &format!(
-                    "SELECT {g_table}.* FROM {s_table}
-                    LEFT JOIN {g_table} ON {s_table}.{id_g} = {g_table}.{id_g}
-                    WHERE {s_table}.{document} @@ to_tsquery($1)
-                    ORDER BY ts_rank({s_table}.{document}, to_tsquery($1)) DESC
-                    LIMIT {} OFFSET {}",
-                    pagination.max,
-                    offset,
-                    id_g = ID_G_COL,
-                    s_table = SEARCHABLE_VIEW,
-                    g_table = G_TABLE,
-                    document = DOCUMENT
-                );
-
-                let gestures = select::<RawGesture>(client, &gestures_query, &[&search]).await?;
-
-                let gestures_count_query = format!(
-                    "SELECT COUNT(*) FROM {}
-                    WHERE {} @@ to_tsquery($1)",
-                    SEARCHABLE_VIEW, DOCUMENT
-                );
-                (gestures, gestures_count_query)
-            }
-            _ => {
-                let gestures_query = format!(
-                    "SELECT * FROM gestures ORDER BY {} DESC LIMIT {} OFFSET {}",
-                    CREATION_COL, pagination.max, offset
-                );
-                let gestures = select::<RawGesture>(client, &gestures_query, &[]).await?;
-
-                let gestures_count_query = "SELECT COUNT(*) FROM gestures".to_owned();
-                (gestures, gestures_count_query)
+        // cursors are positions in the newest first order
+        let sort = match pagination {
+            PaginationRequest::Keyset { .. } => Sort::Newest,
+            PaginationRequest::Page { .. } => sort,
+        };
+        let order = query::order_by(sort, searching, &mut params);
+
+        // keyset pagination reads one more gesture to know if there is a next page
+        let max = pagination.max();
+        let limit = match &pagination {
+            PaginationRequest::Page { page, .. } => format!(
+                "LIMIT {} OFFSET {}",
+                max,
+                u64::from(page.saturating_sub(1)) * u64::from(max)
+            ),
+            PaginationRequest::Keyset { after, .. } => {
+                if let Some(cursor) = after {
+                    conditions.push(format!(
+                        "({g_table}.{creation}, {g_table}.{id_g}) < ({}, {})",
+                        params.push(cursor.creation_date),
+                        params.push(cursor.id),
+                        g_table = G_TABLE,
+                        creation = CREATION_COL,
+                        id_g = ID_G_COL
+                    ));
+                }
+                format!("LIMIT {}", u32::from(max) + 1)
             }
         };
 
+        let gestures_query = format!(
+            "SELECT {}.*{} FROM {} {} ORDER BY {} {}",
+            G_TABLE,
+            if searching {
+                ", matching.matched_in, matching.snippet"
+            } else {
+                ""
+            },
+            from,
+            query::where_clause(&conditions),
+            order,
+            limit
+        );
+        let mut rows = client
+            .query(gestures_query.as_str(), &params.refs())
+            .await?;
+
+        let next_cursor = if rows.len() > usize::from(max) {
+            rows.truncate(usize::from(max));
+            rows.last().map(|row| Cursor {
+                creation_date: row.get(CREATION_COL),
+                id: row.get(ID_G_COL),
+            })
+        } else {
+            None
+        };
+
+        let search_matches = rows
+            .iter()
+            .map(|row| {
+                if searching {
+                    Some(SearchMatch {
+                        matched_in: row.get("matched_in"),
+                        snippet: row.get("snippet"),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<Option<SearchMatch>>>();
+        let gestures = rows
+            .into_iter()
+            .map(from_row::<RawGesture>)
+            .collect::<Result<Vec<RawGesture>, DbError>>()?;
+
         let ids_gestures = gestures.iter().map(|g| g.id_gesture).collect::<Vec<Uuid>>();
 
         // Select evrything from db
+        let gestures_count_query = format!("SELECT COUNT(*) FROM {} {}", from, count_where_clause);
         let gestures_count_query = async {
-            match search {
-                Some(search) => client
-                    .query_one(gestures_count_query.as_str(), &[&search])
-                    .await
-                    .map_err(DbError::from),
-                _ => client
-                    .query_one(gestures_count_query.as_str(), &[])
-                    .await
-                    .map_err(DbError::from),
-            }
+            client
+                .query_one(gestures_count_query.as_str(), &count_params.refs())
+                .await
+                .map_err(DbError::from)
         };
 
         let descriptions_query = format!(
@@ -172,161 +397,777 @@ impl GestureClient {
             P_TABLE, ID_G_COL, CREATION_COL
         );
 
-        let (descriptions, meanings, pictures, total) = future::try_join4(
-            select::<RawDescription>(client, &descriptions_query, &[&ids_gestures]),
-            select::<RawMeaning>(client, &meanings_query, &[&ids_gestures]),
-            select::<RawPicture>(client, &pictures_query, &[&ids_gestures]),
-            gestures_count_query,
-        )
-        .await?;
+        let categories_query = format!(
+            "SELECT gc.* FROM {gc_table} AS gc JOIN {c_table} AS c ON c.{id_c} = gc.{id_c}
+            WHERE gc.{id_g} = ANY($1) ORDER BY c.{position}, c.{creation}",
+            gc_table = GC_TABLE,
+            c_table = C_TABLE,
+            id_c = ID_C_COL,
+            id_g = ID_G_COL,
+            position = POSITION_C_COL,
+            creation = CREATION_COL
+        );
+        let related_query = format!(
+            "SELECT * FROM {} WHERE {} = ANY($1) ORDER BY {}, {}",
+            RELATED_VIEW, ID_G_COL, CREATION_COL, ID_RELATED_COL
+        );
+
+        let facets_query = async {
+            if !facets {
+                return Ok(None);
+            }
+            let tags_query = format!(
+                "SELECT tag, COUNT(*) FROM {} CROSS JOIN unnest({}.{}) AS tag {}
+                GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+                from, G_TABLE, TAGS_COL, count_where_clause
+            );
+            let langs_query = format!(
+                "SELECT gl.{lang}, COUNT(*) FROM {from}
+                JOIN {gl_view} AS gl ON gl.{id_g} = {g_table}.{id_g} {where_clause}
+                GROUP BY gl.{lang} ORDER BY COUNT(*) DESC, gl.{lang}",
+                from = from,
+                where_clause = count_where_clause,
+                g_table = G_TABLE,
+                gl_view = GESTURE_LANGS_VIEW,
+                id_g = ID_G_COL,
+                lang = LANG_S_COL
+            );
+            let params = count_params.refs();
+            let (tags, langs) = future::try_join(
+                client.query(tags_query.as_str(), &params),
+                client.query(langs_query.as_str(), &params),
+            )
+            .await?;
+            Ok::<_, DbError>(Some(Facets {
+                tags: counts(tags),
+                langs: counts(langs),
+            }))
+        };
+
+        let ((descriptions, meanings, pictures, total), (categories, related, facets)) =
+            future::try_join(
+                future::try_join4(
+                    select::<RawDescription>(client, &descriptions_query, &[&ids_gestures]),
+                    select::<RawMeaning>(client, &meanings_query, &[&ids_gestures]),
+                    select::<RawPicture>(client, &pictures_query, &[&ids_gestures]),
+                    gestures_count_query,
+                ),
+                future::try_join3(
+                    select::<RawGestureCategory>(client, &categories_query, &[&ids_gestures]),
+                    select::<RawRelatedGesture>(client, &related_query, &[&ids_gestures]),
+                    facets_query,
+                ),
+            )
+            .await?;
 
         // group every data by gesture id as plain datas
         let (descriptions, _) = group_by_id_gesture(descriptions);
         let (meanings_g, meanings_o) = group_by_id_gesture(meanings);
         let (pictures, _) = group_by_id_gesture(pictures);
+        let (mut categories, _) = group_by_id_gesture(categories);
+        let (mut related, _) = group_by_id_gesture(related);
 
         // group nested description meaning
         let (meanings_d, _) = group_by_id_description(meanings_o);
 
-        let gestures = merge(gestures, descriptions, meanings_g, meanings_d, pictures);
-
-        let total: i64 = total.get(0);
         // merge as nested datas our pre-grouped datas
-        Ok((gestures, total as u16))
+        let gestures = merge(gestures, descriptions, meanings_g, meanings_d, pictures)
+            .into_iter()
+            .zip(search_matches)
+            .zip(&ids_gestures)
+            .map(|((gesture, search_match), id_gesture)| Gesture {
+                categories: categories
+                    .remove(id_gesture)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|category| format!("{}", category.id_category))
+                    .collect(),
+                related: related
+                    .remove(id_gesture)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|link| RelatedGesture {
+                        id: format!("{}", link.id_related),
+                        kind: link.kind,
+                    })
+                    .collect(),
+                search_match,
+                ..gesture
+            })
+            .collect();
+
+        Ok(GesturesPage {
+            gestures,
+            total: total.get(0),
+            facets,
+            next_cursor,
+        })
+    }
+
+    /// Tags and meaning phrases starting with `prefix`, accents and case are ignored, most used first
+    pub async fn suggestions(&self, prefix: &str, max: u16) -> Result<Vec<Suggestion>, DbError> {
+        let suggestions = select::<RawSuggestion>(
+            &self.client,
+            &format!(
+                "SELECT * FROM {} WHERE {} LIKE search_text($1) || '%'
+                ORDER BY {} DESC, {} LIMIT {}",
+                S_TABLE, NORMALIZED_SU_COL, COUNT_SU_COL, TERM_SU_COL, max
+            ),
+            &[&escape_like(prefix)],
+        )
+        .await?;
+
+        Ok(suggestions.into_iter().map(Suggestion::from_raw).collect())
+    }
+
+    /// Every tag with its number of gestures, most used first
+    pub async fn tags(&self) -> Result<Vec<Tag>, DbError> {
+        let rows = self
+            .client
+            .query(
+                format!(
+                    "SELECT tag, COUNT(*) FROM {} CROSS JOIN unnest({}) AS tag
+                    GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+                    G_TABLE, TAGS_COL
+                )
+                .as_str(),
+                &[],
+            )
+            .await?;
+
+        Ok(counts(rows)
+            .into_iter()
+            .map(|(name, count)| Tag { name, count })
+            .collect())
+    }
+
+    /// Rename a tag on every gesture, the new name can't be a tag already used, merge them instead
+    /// Return the number of modified gestures
+    pub async fn rename_tag(&mut self, tag: &str, name: &str) -> Result<u64, DbError> {
+        let tag = normalize_tag(tag);
+        let name = match normalize_tag(name) {
+            normalized if normalized.is_empty() => {
+                return Err(DbError::InvalidTag(name.to_owned()))
+            }
+            normalized => normalized,
+        };
+
+        let transaction = self.transaction().await?;
+        if tag != name {
+            let used = transaction
+                .query_opt(
+                    format!(
+                        "SELECT 1 FROM {} WHERE $1 = ANY({}) LIMIT 1 FOR UPDATE",
+                        G_TABLE, TAGS_COL
+                    )
+                    .as_str(),
+                    &[&name],
+                )
+                .await?;
+            if used.is_some() {
+                return Err(DbError::UniqueViolation(format!("tag {}", name)));
+            }
+        }
+        let modified = replace_tags(&transaction, &[tag], &name).await?;
+        transaction.commit().await?;
+        Ok(modified)
+    }
+
+    /// Replace `tags` by `into` on every gesture at once, a gesture keeps a single `into`
+    /// Return the number of modified gestures
+    pub async fn merge_tags(&mut self, tags: Vec<String>, into: &str) -> Result<u64, DbError> {
+        let tags = normalize_tags(tags);
+        let into = match normalize_tag(into) {
+            normalized if normalized.is_empty() => {
+                return Err(DbError::InvalidTag(into.to_owned()))
+            }
+            normalized => normalized,
+        };
+
+        let transaction = self.transaction().await?;
+        let modified = replace_tags(&transaction, &tags, &into).await?;
+        transaction.commit().await?;
+        Ok(modified)
     }
 
     /// Add a gesture in db
-    pub async fn add_gesture(&self, new_gesture: NewGesture) -> Result<String, DbError> {
+    pub async fn add_gesture(&mut self, new_gesture: NewGesture) -> Result<String, DbError> {
         let new_id = Uuid::new_v4();
-        insert(&self.client, RawGesture::from(new_gesture, new_id))
-            .await
-            .map(|_| new_id.to_hyphenated().to_string())
+        let transaction = self.transaction().await?;
+        insert(&transaction, RawGesture::from(new_gesture, new_id)).await?;
+        transaction.commit().await?;
+        Ok(new_id.to_hyphenated().to_string())
     }
 
+    /// Update a gesture if it is still at `version` (None to skip the check)
+    /// Return the new version
     pub async fn update_gesture(
-        &self,
+        &mut self,
         id: &str,
         updatable_gesture: NewGesture,
-    ) -> Result<(), DbError> {
-        let id = Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
-        update(&self.client, InnerGesture::from(updatable_gesture, id)).await
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, G_TABLE, ID_G_COL, &id, version).await?;
+        let version = update(&transaction, InnerGesture::from(updatable_gesture, id)).await?;
+        transaction.commit().await?;
+        Ok(version)
     }
 
     /// Add a description and nested data in db for a gesture
     pub async fn add_description(
-        &self,
+        &mut self,
         new_description: NewDescription,
         id_gesture: &str,
     ) -> Result<String, DbError> {
-        let id_gesture = Uuid::parse_str(id_gesture)
-            .map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
+        let id_gesture = parse_id(id_gesture)?;
         let new_id = Uuid::new_v4();
 
+        let transaction = self.transaction().await?;
         insert(
-            &self.client,
+            &transaction,
             RawDescription::from(new_description, id_gesture, new_id),
         )
-        .await
-        .map(|_| new_id.to_hyphenated().to_string())
+        .await?;
+        transaction.commit().await?;
+        Ok(new_id.to_hyphenated().to_string())
     }
 
     pub async fn update_description(
-        &self,
+        &mut self,
         id: &str,
         new_description: NewDescription,
-    ) -> Result<(), DbError> {
-        let id = Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
-        update(&self.client, InnerDescription::from(new_description, id)).await
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, D_TABLE, ID_D_COL, &id, version).await?;
+        let version = update(&transaction, InnerDescription::from(new_description, id)).await?;
+        transaction.commit().await?;
+        Ok(version)
     }
 
     /// Add a meaning in db for a gesture or description
     pub async fn add_meaning(
-        &self,
+        &mut self,
         meaning: NewMeaning,
         id_gesture: Option<&str>,
         id_description: Option<&str>,
     ) -> Result<String, DbError> {
-        let id_gesture = match id_gesture.map(|id| {
-            Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))
-        }) {
-            Some(Err(e)) => return Err(e),
-            Some(Ok(i)) => Some(i),
-            None => None,
-        };
-        let id_description = match id_description.map(|id| {
-            Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))
-        }) {
-            Some(Err(e)) => return Err(e),
-            Some(Ok(i)) => Some(i),
-            None => None,
-        };
+        let id_gesture = id_gesture.map(parse_id).transpose()?;
+        let id_description = id_description.map(parse_id).transpose()?;
 
         let new_id = Uuid::new_v4();
 
+        let transaction = self.transaction().await?;
         insert(
-            &self.client,
+            &transaction,
             RawMeaning::from(meaning, id_gesture, id_description, new_id),
         )
-        .await
-        .map(|_| new_id.to_hyphenated().to_string())
+        .await?;
+        transaction.commit().await?;
+        Ok(new_id.to_hyphenated().to_string())
     }
 
-    pub async fn update_meaning(&self, id: &str, new_meaning: NewMeaning) -> Result<(), DbError> {
-        let id = Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
-        update(&self.client, InnerMeaning::from(new_meaning, id)).await
+    pub async fn update_meaning(
+        &mut self,
+        id: &str,
+        new_meaning: NewMeaning,
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, M_TABLE, ID_M_COL, &id, version).await?;
+        let version = update(&transaction, InnerMeaning::from(new_meaning, id)).await?;
+        transaction.commit().await?;
+        Ok(version)
     }
 
     /// Add a picture and nested data in db for a gesture
     pub async fn add_picture(
-        &self,
+        &mut self,
         picture: NewPicture,
         id_gesture: &str,
     ) -> Result<String, DbError> {
-        let id_gesture = Uuid::parse_str(id_gesture)
-            .map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
+        let id_gesture = parse_id(id_gesture)?;
         let new_id = Uuid::new_v4();
 
-        insert(&self.client, RawPicture::from(picture, id_gesture, new_id))
-            .await
-            .map(|_| new_id.to_hyphenated().to_string())
+        let transaction = self.transaction().await?;
+        insert(&transaction, RawPicture::from(picture, id_gesture, new_id)).await?;
+        transaction.commit().await?;
+        Ok(new_id.to_hyphenated().to_string())
     }
 
     pub async fn update_picture_meta(
-        &self,
+        &mut self,
         id: &str,
         new_picture_meta: NewPictureMeta,
-    ) -> Result<(), DbError> {
-        let id = Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
-        update(&self.client, InnerPictureMeta::from(new_picture_meta, id)).await
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, P_TABLE, ID_P_COL, &id, version).await?;
+        let version = update(&transaction, InnerPictureMeta::from(new_picture_meta, id)).await?;
+        transaction.commit().await?;
+        Ok(version)
     }
 
     pub async fn update_picture_format(
-        &self,
+        &mut self,
         id: &str,
         new_picture_file_info: NewPictureFileInfo,
-    ) -> Result<(), DbError> {
-        let id = Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
-        update(
-            &self.client,
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, P_TABLE, ID_P_COL, &id, version).await?;
+        let version = update(
+            &transaction,
             PictureFileInfo::from(new_picture_file_info, id),
         )
-        .await
+        .await?;
+        transaction.commit().await?;
+        Ok(version)
+    }
+
+    /// Set where a description, meaning or picture comes from, None to clear it
+    pub async fn set_source(
+        &mut self,
+        content: Content,
+        id: &str,
+        source: Option<Source>,
+        version: Option<i32>,
+    ) -> Result<i32, DbError> {
+        let id = parse_id(id)?;
+        let (table, id_col) = content.table();
+        let RawSource {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+        } = Source::into_raw(source);
+
+        let transaction = self.transaction().await?;
+        check_version(&transaction, table, id_col, &id, version).await?;
+        let row = transaction
+            .query_one(
+                format!(
+                    "UPDATE {table} SET {}=$1, {}=$2, {}=$3, {}=$4, {}=$5, {version}={version} + 1
+                    WHERE {id_col}=$6 RETURNING {version}",
+                    SOURCE_COL,
+                    SOURCE_PAGE_COL,
+                    SOURCE_REFERENCE_COL,
+                    ATTRIBUTION_COL,
+                    LICENSE_COL,
+                    table = table,
+                    id_col = id_col,
+                    version = VERSION_COL
+                )
+                .as_str(),
+                &[
+                    &source,
+                    &source_page,
+                    &source_reference,
+                    &attribution,
+                    &license,
+                    &id,
+                ],
+            )
+            .await?;
+        transaction.commit().await?;
+        Ok(row.get(VERSION_COL))
+    }
+
+    /// Add gestures with their content in a single transaction, nothing is added if one fails
+    /// Rows are created a microsecond apart in the order they are given
+    /// With `dry_run` everything is inserted then rolled back, to check the import
+    pub async fn import_gestures(
+        &mut self,
+        gestures: Vec<NewGestureTree>,
+        dry_run: bool,
+    ) -> Result<Vec<ImportedGesture>, DbError> {
+        let transaction = self.transaction().await?;
+        let mut imported = Vec::with_capacity(gestures.len());
+        let now: NaiveDateTime = transaction
+            .query_one("SELECT NOW()::timestamp", &[])
+            .await?
+            .get(0);
+        let mut dates = (0..).map(|n| now + Duration::microseconds(n));
+
+        for tree in gestures {
+            let NewGestureTree {
+                gesture,
+                descriptions,
+                meanings,
+                pictures,
+            } = tree;
+            let id_gesture = Uuid::new_v4();
+            insert(
+                &transaction,
+                Dated {
+                    item: RawGesture::from(gesture, id_gesture),
+                    creation_date: dates.next(),
+                    version: 1,
+                },
+            )
+            .await?;
+
+            for (description, description_meanings) in descriptions {
+                let id_description = Uuid::new_v4();
+                insert(
+                    &transaction,
+                    Dated {
+                        item: RawDescription::from(description, id_gesture, id_description),
+                        creation_date: dates.next(),
+                        version: 1,
+                    },
+                )
+                .await?;
+                for meaning in description_meanings {
+                    insert(
+                        &transaction,
+                        Dated {
+                            item: RawMeaning::from(
+                                meaning,
+                                None,
+                                Some(id_description),
+                                Uuid::new_v4(),
+                            ),
+                            creation_date: dates.next(),
+                            version: 1,
+                        },
+                    )
+                    .await?;
+                }
+            }
+
+            for meaning in meanings {
+                insert(
+                    &transaction,
+                    Dated {
+                        item: RawMeaning::from(meaning, Some(id_gesture), None, Uuid::new_v4()),
+                        creation_date: dates.next(),
+                        version: 1,
+                    },
+                )
+                .await?;
+            }
+
+            let mut id_pictures = Vec::with_capacity(pictures.len());
+            for (id, picture) in pictures {
+                let id_picture = parse_id(&id)?;
+                insert(
+                    &transaction,
+                    Dated {
+                        item: RawPicture::from(picture, id_gesture, id_picture),
+                        creation_date: dates.next(),
+                        version: 1,
+                    },
+                )
+                .await?;
+                id_pictures.push(id);
+            }
+
+            imported.push(ImportedGesture {
+                id: id_gesture.to_hyphenated().to_string(),
+                pictures: id_pictures,
+            });
+        }
+
+        if dry_run {
+            transaction.rollback().await?;
+        } else {
+            transaction.commit().await?;
+        }
+        Ok(imported)
+    }
+
+    /// Creation date of every gesture, description, meaning and picture by id, for exports
+    pub async fn creation_dates(&self) -> Result<HashMap<String, NaiveDateTime>, DbError> {
+        let query = [
+            (G_TABLE, ID_G_COL),
+            (D_TABLE, ID_D_COL),
+            (M_TABLE, ID_M_COL),
+            (P_TABLE, ID_P_COL),
+        ]
+        .iter()
+        .map(|(table, id_col)| format!("SELECT {}, {} FROM {}", id_col, CREATION_COL, table))
+        .collect::<Vec<String>>()
+        .join(" UNION ALL ");
+        let rows = self.client.query(query.as_str(), &[]).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let id: Uuid = row.get(0);
+                (id.to_hyphenated().to_string(), row.get(1))
+            })
+            .collect())
+    }
+
+    /// Add back exported languages, categories then gestures with their ids in a single
+    /// transaction, with the creation dates of `creation_dates` by id and their versions
+    /// Languages and categories already there are kept, nothing is added if a gesture already
+    /// exists or belongs to an unknown category
+    pub async fn restore_gestures(
+        &mut self,
+        languages: Vec<Language>,
+        categories: Vec<Category>,
+        gestures: Vec<Gesture>,
+        creation_dates: HashMap<String, NaiveDateTime>,
+    ) -> Result<(), DbError> {
+        let transaction = self.transaction().await?;
+        let mut links = Vec::new();
+
+        for language in languages {
+            insert_missing(&transaction, RawLanguage::from(language)).await?;
+        }
+
+        // parents first, the labels of a category already there are left as they are
+        let mut categories = categories;
+        while let Some(category) = categories.pop() {
+            let Category {
+                id,
+                id_parent,
+                position,
+                labels,
+                children,
+            } = category;
+            let id_category = parse_id(&id)?;
+            let raw = RawCategory {
+                id_category,
+                id_parent: id_parent.as_deref().map(parse_id).transpose()?,
+                position,
+            };
+            if insert_missing(&transaction, raw).await? {
+                insert_category_labels(&transaction, id_category, labels).await?;
+            }
+            categories.extend(children);
+        }
+
+        for gesture in gestures {
+            let Gesture {
+                id,
+                version,
+                tags,
+                descriptions,
+                meanings,
+                pictures,
+                categories,
+                related,
+                ..
+            } = gesture;
+            let id_gesture = parse_id(&id)?;
+            for RelatedGesture { id, kind } in related {
+                let id_related = parse_id(&id)?;
+                links.push(if kind == Relation::HasVariant.kind() {
+                    (
+                        id_related,
+                        id_gesture,
+                        Relation::VariantOf.kind().to_owned(),
+                    )
+                } else {
+                    (id_gesture, id_related, kind)
+                });
+            }
+            insert(
+                &transaction,
+                Dated {
+                    item: RawGesture::from(NewGesture { tags }, id_gesture),
+                    creation_date: creation_dates.get(&id).copied(),
+                    version,
+                },
+            )
+            .await?;
+
+            // an unknown category fails the restore rather than silently dropping the membership
+            let ids_categories = categories
+                .iter()
+                .map(String::as_str)
+                .map(parse_id)
+                .collect::<Result<Vec<Uuid>, DbError>>()?;
+            transaction
+                .execute(
+                    format!(
+                        "INSERT INTO {} ({}, {}) SELECT $1, unnest($2::uuid[])",
+                        GC_TABLE, ID_G_COL, ID_C_COL
+                    )
+                    .as_str(),
+                    &[&id_gesture, &ids_categories],
+                )
+                .await?;
+
+            for description in descriptions {
+                let Description {
+                    id,
+                    version,
+                    value,
+                    langs,
+                    meanings,
+                    translation_group,
+                    source,
+                    ..
+                } = description;
+                let id_description = parse_id(&id)?;
+                let mut raw = RawDescription::from(
+                    NewDescription { value, langs },
+                    id_gesture,
+                    id_description,
+                )
+                .with_source(source);
+                raw.translation_group = translation_group.as_deref().map(parse_id).transpose()?;
+                let creation_date = creation_dates.get(&id).copied();
+                insert(
+                    &transaction,
+                    Dated {
+                        item: raw,
+                        creation_date,
+                        version,
+                    },
+                )
+                .await?;
+                for Meaning {
+                    id,
+                    version,
+                    value,
+                    langs,
+                    translation_group,
+                    regions,
+                    register,
+                    source,
+                    ..
+                } in meanings
+                {
+                    let mut raw = RawMeaning::from(
+                        NewMeaning {
+                            value,
+                            langs,
+                            regions,
+                            register,
+                        },
+                        None,
+                        Some(id_description),
+                        parse_id(&id)?,
+                    )
+                    .with_source(source);
+                    raw.translation_group =
+                        translation_group.as_deref().map(parse_id).transpose()?;
+                    let creation_date = creation_dates.get(&id).copied();
+                    insert(
+                        &transaction,
+                        Dated {
+                            item: raw,
+                            creation_date,
+                            version,
+                        },
+                    )
+                    .await?;
+                }
+            }
+
+            for Meaning {
+                id,
+                version,
+                value,
+                langs,
+                translation_group,
+                regions,
+                register,
+                source,
+                ..
+            } in meanings
+            {
+                let mut raw = RawMeaning::from(
+                    NewMeaning {
+                        value,
+                        langs,
+                        regions,
+                        register,
+                    },
+                    Some(id_gesture),
+                    None,
+                    parse_id(&id)?,
+                )
+                .with_source(source);
+                raw.translation_group = translation_group.as_deref().map(parse_id).transpose()?;
+                let creation_date = creation_dates.get(&id).copied();
+                insert(
+                    &transaction,
+                    Dated {
+                        item: raw,
+                        creation_date,
+                        version,
+                    },
+                )
+                .await?;
+            }
+
+            for Picture {
+                id,
+                version,
+                langs,
+                format,
+                source,
+                ..
+            } in pictures
+            {
+                insert(
+                    &transaction,
+                    Dated {
+                        item: RawPicture::from(
+                            NewPicture { langs, format },
+                            id_gesture,
+                            parse_id(&id)?,
+                        )
+                        .with_source(source),
+                        creation_date: creation_dates.get(&id).copied(),
+                        version,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        // links are restored once both gestures are there, each is listed by both gestures
+        for (id_gesture, id_related, kind) in links {
+            transaction
+                .execute(
+                    format!(
+                        "INSERT INTO {gl_table} ({id_g}, {id_r}, {kind})
+                        SELECT $1, $2, $3 FROM {g_table} WHERE {id_g} IN ($1, $2)
+                        HAVING COUNT(*) = 2 ON CONFLICT DO NOTHING",
+                        gl_table = GL_TABLE,
+                        g_table = G_TABLE,
+                        id_g = ID_G_COL,
+                        id_r = ID_RELATED_COL,
+                        kind = KIND_GL_COL
+                    )
+                    .as_str(),
+                    &[&id_gesture, &id_related, &kind],
+                )
+                .await?;
+        }
+
+        transaction.commit().await.map_err(DbError::from)
     }
 
     /// Delete gesture and nested object from db
-    pub async fn delete_gesture_cascade(&self, id: &str) -> Result<(), DbError> {
-        delete(
-            &self.client,
-            G_TABLE,
-            ID_G_COL,
-            &Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?,
-        )
-        .await
+    pub async fn delete_gesture_cascade(
+        &mut self,
+        id: &str,
+        version: Option<i32>,
+    ) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, G_TABLE, ID_G_COL, &id, version).await?;
+        delete(&transaction, G_TABLE, ID_G_COL, &id).await?;
+        transaction.commit().await.map_err(DbError::from)
     }
 
     pub async fn get_picture_format(&self, id: &str) -> Result<String, DbError> {
-        let uuid =
-            Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?;
+        let uuid = parse_id(id)?;
 
         let query = format!(
             "SELECT {} FROM {} WHERE {}=$1",
@@ -340,36 +1181,487 @@ impl GestureClient {
     }
 
     /// Delete description and nested data from db
-    pub async fn delete_description_cascade(&self, id: &str) -> Result<(), DbError> {
-        delete(
-            &self.client,
-            D_TABLE,
-            ID_D_COL,
-            &Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?,
-        )
-        .await
+    pub async fn delete_description_cascade(
+        &mut self,
+        id: &str,
+        version: Option<i32>,
+    ) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, D_TABLE, ID_D_COL, &id, version).await?;
+        delete(&transaction, D_TABLE, ID_D_COL, &id).await?;
+        transaction.commit().await.map_err(DbError::from)
     }
 
     /// Delete meaning from db
-    pub async fn delete_meaning(&self, id: &str) -> Result<(), DbError> {
-        delete(
+    pub async fn delete_meaning(&mut self, id: &str, version: Option<i32>) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, M_TABLE, ID_M_COL, &id, version).await?;
+        delete(&transaction, M_TABLE, ID_M_COL, &id).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Delete picture from db
+    pub async fn delete_picture(&mut self, id: &str, version: Option<i32>) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        check_version(&transaction, P_TABLE, ID_P_COL, &id, version).await?;
+        delete(&transaction, P_TABLE, ID_P_COL, &id).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Retrieve every revision of a gesture and its nested data, newest first
+    pub async fn gesture_history(&self, id_gesture: &str) -> Result<Vec<Revision>, DbError> {
+        let id_gesture = parse_id(id_gesture)?;
+
+        let revisions = select::<RawRevision>(
             &self.client,
-            M_TABLE,
-            ID_M_COL,
-            &Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?,
+            &format!(
+                "SELECT * FROM {} WHERE {} = $1 ORDER BY {} DESC",
+                H_TABLE, ID_G_COL, ID_H_COL
+            ),
+            &[&id_gesture],
         )
-        .await
+        .await?;
+
+        Ok(revisions.into_iter().map(Revision::from_raw).collect())
     }
 
-    /// Delete picture from db
-    pub async fn delete_picture(&self, id: &str) -> Result<(), DbError> {
-        delete(
+    /// Restore an entity as it was right after a revision
+    /// A deletion revision deletes the entity again
+    pub async fn revert_revision(&mut self, id_revision: i64) -> Result<(), DbError> {
+        let transaction = self.transaction().await?;
+
+        let row = transaction
+            .query_opt(
+                format!("SELECT * FROM {} WHERE {} = $1", H_TABLE, ID_H_COL).as_str(),
+                &[&id_revision],
+            )
+            .await?;
+        let revision = match row {
+            Some(row) => from_row::<RawRevision>(row)?,
+            _ => return Err(DbError::NotFound),
+        };
+
+        let (table, id_col, columns) = revertable_columns(&revision.entity).ok_or_else(|| {
+            DbError::Other(format!("Entity {} can't be reverted", revision.entity))
+        })?;
+
+        match &revision.after {
+            Some(after) => {
+                transaction
+                    .execute(revert_query(table, id_col, columns).as_str(), &[after])
+                    .await?;
+            }
+            _ => delete(&transaction, table, id_col, &revision.id_entity).await?,
+        };
+
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Make an item the translation of another of the same gesture, they then share a translation group
+    /// The item leaves the group it was in
+    pub async fn link_translation(
+        &mut self,
+        kind: Translatable,
+        id: &str,
+        id_original: &str,
+    ) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let id_original = parse_id(id_original)?;
+        if id == id_original {
+            return Err(DbError::InvalidTranslation(
+                "an item can't be its own translation".to_owned(),
+            ));
+        }
+        let (table, id_col) = kind.table();
+
+        let transaction = self.transaction().await?;
+        let rows = transaction
+            .query(
+                format!(
+                    "SELECT {id}, {id_g}, {group} FROM {view} WHERE {kind} = $1 AND {id} = ANY($2)",
+                    id = ID_T_COL,
+                    id_g = ID_G_COL,
+                    group = TRANSLATION_GROUP_COL,
+                    view = TRANSLATABLE_VIEW,
+                    kind = KIND_T_COL
+                )
+                .as_str(),
+                &[&kind.kind(), &vec![id, id_original]],
+            )
+            .await?;
+        let find = |wanted: &Uuid| {
+            rows.iter()
+                .find(|row| row.get::<_, Uuid>(0) == *wanted)
+                .map(|row| (row.get::<_, Uuid>(1), row.get::<_, Option<Uuid>>(2)))
+                .ok_or(DbError::NotFound)
+        };
+        let (id_gesture, previous_group) = find(&id)?;
+        let (original_id_gesture, original_group) = find(&id_original)?;
+        if id_gesture != original_id_gesture {
+            return Err(DbError::InvalidTranslation(
+                "a translation belongs to the same gesture".to_owned(),
+            ));
+        }
+
+        // the first item of a group names it
+        let group = original_group.unwrap_or(id_original);
+        transaction
+            .execute(
+                format!(
+                    "UPDATE {} SET {} = $1 WHERE {} = ANY($2)",
+                    table, TRANSLATION_GROUP_COL, id_col
+                )
+                .as_str(),
+                &[&group, &vec![id, id_original]],
+            )
+            .await?;
+        if let Some(previous_group) = previous_group.filter(|previous| *previous != group) {
+            remove_lone_translation(&transaction, table, previous_group).await?;
+        }
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Remove an item from its translation group, a group left with one item is removed too
+    pub async fn unlink_translation(
+        &mut self,
+        kind: Translatable,
+        id: &str,
+    ) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let (table, id_col) = kind.table();
+
+        let transaction = self.transaction().await?;
+        let group: Option<Uuid> = match transaction
+            .query_opt(
+                format!(
+                    "SELECT {} FROM {} WHERE {} = $1 FOR UPDATE",
+                    TRANSLATION_GROUP_COL, table, id_col
+                )
+                .as_str(),
+                &[&id],
+            )
+            .await?
+        {
+            Some(row) => row.get(0),
+            _ => return Err(DbError::NotFound),
+        };
+
+        if let Some(group) = group {
+            transaction
+                .execute(
+                    format!(
+                        "UPDATE {} SET {} = NULL WHERE {} = $1",
+                        table, TRANSLATION_GROUP_COL, id_col
+                    )
+                    .as_str(),
+                    &[&id],
+                )
+                .await?;
+            remove_lone_translation(&transaction, table, group).await?;
+        }
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Descriptions and meanings not in `lang` and without a translation in `lang`,
+    /// those of the newest gestures first
+    pub async fn missing_translations(
+        &self,
+        lang: &str,
+        max: u16,
+        page: u32,
+    ) -> Result<MissingTranslations, DbError> {
+        let missing = format!(
+            "FROM {view} AS t JOIN {g_table} AS g ON g.{id_g} = t.{id_g}
+            WHERE NOT $1 = ANY(t.{langs})
+            AND (t.{group} IS NULL OR NOT EXISTS (
+                SELECT 1 FROM {view} AS o
+                WHERE o.{kind} = t.{kind} AND o.{group} = t.{group} AND $1 = ANY(o.{langs})
+            ))",
+            view = TRANSLATABLE_VIEW,
+            g_table = G_TABLE,
+            id_g = ID_G_COL,
+            langs = LANG_D_COL,
+            group = TRANSLATION_GROUP_COL,
+            kind = KIND_T_COL
+        );
+
+        let items = select::<RawTranslatable>(
+            &self.client,
+            &format!(
+                "SELECT t.* {} ORDER BY g.{} DESC, t.{}, t.{}, t.{} LIMIT {} OFFSET {}",
+                missing,
+                CREATION_COL,
+                ID_G_COL,
+                KIND_T_COL,
+                ID_T_COL,
+                max,
+                (i64::from(page) - 1) * i64::from(max)
+            ),
+            &[&lang],
+        );
+        let total = self
+            .client
+            .query_one(format!("SELECT COUNT(*) {}", missing).as_str(), &[&lang]);
+        let (items, total) =
+            future::try_join(items, async { total.await.map_err(DbError::from) }).await?;
+
+        Ok(MissingTranslations {
+            items: items
+                .into_iter()
+                .map(MissingTranslation::from_raw)
+                .collect(),
+            total: total.get(0),
+        })
+    }
+
+    /// Every language content can be written in, by code
+    pub async fn languages(&self) -> Result<Vec<Language>, DbError> {
+        let languages = select::<RawLanguage>(
             &self.client,
-            P_TABLE,
-            ID_P_COL,
-            &Uuid::parse_str(id).map_err(|e| DbError::Other(format!("Wrong uuid {:?}", e)))?,
+            &format!("SELECT * FROM {} ORDER BY {}", L_TABLE, CODE_L_COL),
+            &[],
         )
-        .await
+        .await?;
+
+        Ok(languages.into_iter().map(Language::from_raw).collect())
+    }
+
+    /// Add a language, its code is unique whatever its case
+    pub async fn add_language(&mut self, language: Language) -> Result<(), DbError> {
+        let transaction = self.transaction().await?;
+        insert(&transaction, RawLanguage::from(language)).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Rename a language, its code matches whatever its case
+    pub async fn update_language(&mut self, code: &str, name: &str) -> Result<(), DbError> {
+        let transaction = self.transaction().await?;
+        let updated = transaction
+            .execute(
+                format!(
+                    "UPDATE {} SET {} = $2 WHERE lower({}) = lower($1)",
+                    L_TABLE, NAME_L_COL, CODE_L_COL
+                )
+                .as_str(),
+                &[&code, &name],
+            )
+            .await?;
+        if updated == 0 {
+            return Err(DbError::NotFound);
+        }
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Remove a language, its code matches whatever its case, content already written in it keeps it
+    pub async fn delete_language(&mut self, code: &str) -> Result<(), DbError> {
+        let transaction = self.transaction().await?;
+        let deleted = transaction
+            .execute(
+                format!(
+                    "DELETE FROM {} WHERE lower({}) = lower($1)",
+                    L_TABLE, CODE_L_COL
+                )
+                .as_str(),
+                &[&code],
+            )
+            .await?;
+        if deleted == 0 {
+            return Err(DbError::NotFound);
+        }
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Tree of every category, siblings ordered by position then creation
+    pub async fn categories(&self) -> Result<Vec<Category>, DbError> {
+        let (categories, labels) = future::try_join(
+            select::<RawCategory>(
+                &self.client,
+                &format!(
+                    "SELECT * FROM {} ORDER BY {}, {}",
+                    C_TABLE, POSITION_C_COL, CREATION_COL
+                ),
+                &[],
+            ),
+            select::<RawCategoryLabel>(&self.client, &format!("SELECT * FROM {}", CL_TABLE), &[]),
+        )
+        .await?;
+
+        Ok(category_tree(categories, labels))
+    }
+
+    /// Add a category below `id_parent`, or at the top without it
+    pub async fn add_category(&mut self, new_category: NewCategory) -> Result<String, DbError> {
+        let NewCategory {
+            id_parent,
+            position,
+            labels,
+        } = new_category;
+        let id_category = Uuid::new_v4();
+
+        let transaction = self.transaction().await?;
+        insert(
+            &transaction,
+            RawCategory {
+                id_category,
+                id_parent: id_parent.as_deref().map(parse_id).transpose()?,
+                position,
+            },
+        )
+        .await?;
+        insert_category_labels(&transaction, id_category, labels).await?;
+        transaction.commit().await?;
+        Ok(id_category.to_hyphenated().to_string())
+    }
+
+    /// Move, reorder and relabel a category, it can't be moved below itself
+    pub async fn update_category(
+        &mut self,
+        id: &str,
+        category: NewCategory,
+    ) -> Result<(), DbError> {
+        let NewCategory {
+            id_parent,
+            position,
+            labels,
+        } = category;
+        let id_category = parse_id(id)?;
+        let id_parent = id_parent.as_deref().map(parse_id).transpose()?;
+
+        let transaction = self.transaction().await?;
+        if let Some(id_parent) = id_parent {
+            let below_itself: bool = transaction
+                .query_one(
+                    "SELECT $2 IN (SELECT category_subtree($1))",
+                    &[&id_category, &id_parent],
+                )
+                .await?
+                .get(0);
+            if below_itself {
+                return Err(DbError::InvalidCategory(
+                    "a category can't be moved below itself".to_owned(),
+                ));
+            }
+        }
+
+        let updated = transaction
+            .execute(
+                format!(
+                    "UPDATE {} SET {} = $2, {} = $3 WHERE {} = $1",
+                    C_TABLE, ID_PARENT_C_COL, POSITION_C_COL, ID_C_COL
+                )
+                .as_str(),
+                &[&id_category, &id_parent, &position],
+            )
+            .await?;
+        if updated == 0 {
+            return Err(DbError::NotFound);
+        }
+        transaction
+            .execute(
+                format!("DELETE FROM {} WHERE {} = $1", CL_TABLE, ID_C_COL).as_str(),
+                &[&id_category],
+            )
+            .await?;
+        insert_category_labels(&transaction, id_category, labels).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Remove a category and its subcategories, their gestures stay
+    pub async fn delete_category(&mut self, id: &str) -> Result<(), DbError> {
+        let id = parse_id(id)?;
+        let transaction = self.transaction().await?;
+        delete(&transaction, C_TABLE, ID_C_COL, &id).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Replace the categories a gesture belongs to
+    pub async fn set_gesture_categories(
+        &mut self,
+        id_gesture: &str,
+        categories: Vec<String>,
+    ) -> Result<(), DbError> {
+        let id_gesture = parse_id(id_gesture)?;
+        let mut ids_categories = Vec::with_capacity(categories.len());
+        for category in categories {
+            let id_category = parse_id(&category)?;
+            if !ids_categories.contains(&id_category) {
+                ids_categories.push(id_category);
+            }
+        }
+
+        let transaction = self.transaction().await?;
+        check_version(&transaction, G_TABLE, ID_G_COL, &id_gesture, None).await?;
+        transaction
+            .execute(
+                format!("DELETE FROM {} WHERE {} = $1", GC_TABLE, ID_G_COL).as_str(),
+                &[&id_gesture],
+            )
+            .await?;
+        for id_category in ids_categories {
+            insert(
+                &transaction,
+                RawGestureCategory {
+                    id_gesture,
+                    id_category,
+                },
+            )
+            .await?;
+        }
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Link two gestures, it replaces any previous link between them
+    /// A has_variant link is kept as the variant_of link of the related gesture
+    pub async fn set_related_gesture(
+        &mut self,
+        id_gesture: &str,
+        id_related: &str,
+        relation: Relation,
+    ) -> Result<(), DbError> {
+        let id_gesture = parse_id(id_gesture)?;
+        let id_related = parse_id(id_related)?;
+        if id_gesture == id_related {
+            return Err(DbError::InvalidRelation(
+                "a gesture can't be related to itself".to_owned(),
+            ));
+        }
+        let link = match relation {
+            Relation::HasVariant => RawGestureLink {
+                id_gesture: id_related,
+                id_related: id_gesture,
+                kind: Relation::VariantOf.kind().to_owned(),
+            },
+            relation => RawGestureLink {
+                id_gesture,
+                id_related,
+                kind: relation.kind().to_owned(),
+            },
+        };
+
+        let transaction = self.transaction().await?;
+        check_version(&transaction, G_TABLE, ID_G_COL, &id_gesture, None).await?;
+        delete_gesture_link(&transaction, id_gesture, id_related).await?;
+        insert(&transaction, link).await?;
+        transaction.commit().await.map_err(DbError::from)
+    }
+
+    /// Remove the link between two gestures, whichever holds it
+    pub async fn delete_related_gesture(
+        &mut self,
+        id_gesture: &str,
+        id_related: &str,
+    ) -> Result<(), DbError> {
+        let id_gesture = parse_id(id_gesture)?;
+        let id_related = parse_id(id_related)?;
+
+        let transaction = self.transaction().await?;
+        if delete_gesture_link(&transaction, id_gesture, id_related).await? == 0 {
+            return Err(DbError::NotFound);
+        }
+        transaction.commit().await.map_err(DbError::from)
     }
 
     pub async fn get_user(&self, username: &str) -> Result<Option<User>, DbError> {
@@ -385,41 +1677,143 @@ impl GestureClient {
 }
 
 /// Query the bdd
-async fn select<T: FromTokioPostgresRow>(
+async fn select<T: FromTokioPostgresRow + Columns>(
     client: &Client,
     sql: &str,
     params: &[&(dyn ToSql + Sync)],
 ) -> Result<Vec<T>, DbError> {
     let rows = client.query(sql, params).await?;
 
-    Ok(rows
-        .into_iter()
-        .map(T::from_row)
-        .map(Result::unwrap)
-        .collect())
+    rows.into_iter().map(from_row::<T>).collect()
+}
+
+/// FROM and WHERE conditions of the gestures matching a search and a filter,
+/// FROM joins the `matching` search query when the search has words
+fn gestures_selection(
+    search: Option<SearchRequest>,
+    filter: FilterRequest,
+    params: &mut Params,
+) -> Result<(String, Vec<String>, bool), DbError> {
+    // input without any word lists every gesture
+    let (from, searching) = match search.and_then(|s| search::matching_query(s, params)) {
+        Some(matching) => (
+            format!(
+                "{g_table} JOIN ({matching}) AS matching ON matching.{id_g} = {g_table}.{id_g}",
+                g_table = G_TABLE,
+                id_g = ID_G_COL,
+                matching = matching
+            ),
+            true,
+        ),
+        _ => (G_TABLE.to_owned(), false),
+    };
+
+    Ok((from, query::filter_conditions(filter, params)?, searching))
+}
+
+/// Text matched literally by LIKE
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Values with their count from `SELECT value, COUNT(*)` rows
+fn counts(rows: Vec<Row>) -> Vec<(String, i64)> {
+    rows.iter().map(|row| (row.get(0), row.get(1))).collect()
+}
+
+/// Decode a row, a failure is reported with the faulty column
+fn from_row<T: FromTokioPostgresRow + Columns>(row: Row) -> Result<T, DbError> {
+    check_columns::<T>(row.columns())?;
+
+    T::from_row(row).map_err(|e| DbError::Mapping {
+        column: T::TABLE.to_owned(),
+        cause: format!("{:?}", e),
+    })
+}
+
+/// Check every column of `T` is present with a decodable type
+fn check_columns<T: Columns>(columns: &[Column]) -> Result<(), DbError> {
+    for (name, accepts) in T::COLUMNS {
+        let column = format!("{}.{}", T::TABLE, name);
+        match columns.iter().find(|c| c.name() == *name) {
+            Some(found) if accepts(found.type_()) => {}
+            Some(found) => {
+                return Err(DbError::Mapping {
+                    column,
+                    cause: format!("unexpected type {}", found.type_()),
+                })
+            }
+            _ => {
+                return Err(DbError::Mapping {
+                    column,
+                    cause: "missing column".to_owned(),
+                })
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Query the bdd
-async fn insert<T: Insertable>(client: &Client, item: T) -> Result<(), DbError> {
+async fn insert<T: Insertable>(client: &Transaction<'_>, item: T) -> Result<(), DbError> {
     client
         .execute(item.insert_query().as_ref() as &str, &item.query_params())
         .await?;
     Ok(())
 }
 
-async fn update<T: Updatable>(client: &Client, item: T) -> Result<(), DbError> {
-    let nb_modif = client
-        .execute(item.update_query().as_ref() as &str, &item.query_params())
+/// Insert unless a row with the same key is there, return whether it was inserted
+async fn insert_missing<T: Insertable>(client: &Transaction<'_>, item: T) -> Result<bool, DbError> {
+    let query = format!("{} ON CONFLICT DO NOTHING", item.insert_query());
+    let inserted = client.execute(query.as_str(), &item.query_params()).await?;
+    Ok(inserted == 1)
+}
+
+/// Update and return the new version
+async fn update<T: Updatable>(client: &Transaction<'_>, item: T) -> Result<i32, DbError> {
+    let row = client
+        .query_opt(item.update_query().as_ref() as &str, &item.query_params())
         .await?;
 
-    if nb_modif > 0 {
-        Ok(())
-    } else {
-        Err(DbError::NotFound)
+    match row {
+        Some(row) => Ok(row.get(VERSION_COL)),
+        _ => Err(DbError::NotFound),
     }
 }
 
-pub async fn delete(client: &Client, table: &str, id_col: &str, id: &Uuid) -> Result<(), DbError> {
+/// Lock a row and check it is still at the expected version (None to only check it exists)
+async fn check_version(
+    client: &Transaction<'_>,
+    table: &str,
+    id_col: &str,
+    id: &Uuid,
+    expected: Option<i32>,
+) -> Result<(), DbError> {
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {} = $1 FOR UPDATE",
+        VERSION_COL, table, id_col
+    );
+    let row = client.query_opt(sql.as_str(), &[id]).await?;
+
+    let version: i32 = match row {
+        Some(row) => row.get(VERSION_COL),
+        _ => return Err(DbError::NotFound),
+    };
+
+    match expected {
+        Some(expected) if expected != version => Err(DbError::VersionMismatch),
+        _ => Ok(()),
+    }
+}
+
+pub async fn delete(
+    client: &Transaction<'_>,
+    table: &str,
+    id_col: &str,
+    id: &Uuid,
+) -> Result<(), DbError> {
     let sql = format!("DELETE FROM {} WHERE {} = $1", table, id_col);
     let sql: &str = sql.as_ref();
 
@@ -431,6 +1825,153 @@ pub async fn delete(client: &Client, table: &str, id_col: &str, id: &Uuid) -> Re
     }
 }
 
+/// Delete the link between two gestures in both directions and return the number of rows deleted
+async fn delete_gesture_link(
+    client: &Transaction<'_>,
+    id_gesture: Uuid,
+    id_related: Uuid,
+) -> Result<u64, DbError> {
+    client
+        .execute(
+            format!(
+                "DELETE FROM {gl_table}
+                WHERE ({id_g} = $1 AND {id_r} = $2) OR ({id_g} = $2 AND {id_r} = $1)",
+                gl_table = GL_TABLE,
+                id_g = ID_G_COL,
+                id_r = ID_RELATED_COL
+            )
+            .as_str(),
+            &[&id_gesture, &id_related],
+        )
+        .await
+        .map_err(DbError::from)
+}
+
+async fn insert_category_labels(
+    client: &Transaction<'_>,
+    id_category: Uuid,
+    labels: BTreeMap<String, String>,
+) -> Result<(), DbError> {
+    for (lang, label) in labels {
+        insert(
+            client,
+            RawCategoryLabel {
+                id_category,
+                lang,
+                label,
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Nest categories below their parent, keeping their order
+/// A category whose parent is missing is left out
+fn category_tree(categories: Vec<RawCategory>, labels: Vec<RawCategoryLabel>) -> Vec<Category> {
+    let mut labels_by_category: HashMap<Uuid, BTreeMap<String, String>> = HashMap::new();
+    for RawCategoryLabel {
+        id_category,
+        lang,
+        label,
+    } in labels
+    {
+        labels_by_category
+            .entry(id_category)
+            .or_default()
+            .insert(lang, label);
+    }
+
+    let mut children: HashMap<Option<Uuid>, Vec<RawCategory>> = HashMap::new();
+    for category in categories {
+        children
+            .entry(category.id_parent)
+            .or_default()
+            .push(category);
+    }
+
+    fn nest(
+        id_parent: Option<Uuid>,
+        children: &mut HashMap<Option<Uuid>, Vec<RawCategory>>,
+        labels: &mut HashMap<Uuid, BTreeMap<String, String>>,
+    ) -> Vec<Category> {
+        children
+            .remove(&id_parent)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|raw| {
+                let subcategories = nest(Some(raw.id_category), children, labels);
+                let labels = labels.remove(&raw.id_category).unwrap_or_default();
+                Category::from_raw(raw, labels, subcategories)
+            })
+            .collect()
+    }
+
+    nest(None, &mut children, &mut labels_by_category)
+}
+
+/// Replace `tags` by `into` on the gestures having one of them, keeping the order of the tags
+/// NotFound when no gesture has them
+async fn replace_tags(
+    client: &Transaction<'_>,
+    tags: &[String],
+    into: &str,
+) -> Result<u64, DbError> {
+    let sql = format!(
+        "UPDATE {g_table} SET {tags} = ARRAY(
+            SELECT CASE WHEN tag = ANY($1) THEN $2 ELSE tag END
+            FROM unnest({tags}) WITH ORDINALITY AS t(tag, position)
+            GROUP BY 1 ORDER BY MIN(position)
+        ), {version} = {version} + 1
+        WHERE {tags} && $1",
+        g_table = G_TABLE,
+        tags = TAGS_COL,
+        version = VERSION_COL
+    );
+    let modified = client.execute(sql.as_str(), &[&tags, &into]).await?;
+    if modified < 1 {
+        Err(DbError::NotFound)
+    } else {
+        Ok(modified)
+    }
+}
+
+/// Dissolve a translation group with a single item left
+async fn remove_lone_translation(
+    client: &Transaction<'_>,
+    table: &str,
+    group: Uuid,
+) -> Result<(), DbError> {
+    let sql = format!(
+        "UPDATE {table} SET {group} = NULL WHERE {group} = $1
+        AND (SELECT COUNT(*) FROM {table} WHERE {group} = $1) = 1",
+        table = table,
+        group = TRANSLATION_GROUP_COL
+    );
+    client.execute(sql.as_str(), &[&group]).await?;
+    Ok(())
+}
+
+/// Upsert an entity from a jsonb snapshot ($1) of its row
+fn revert_query(table: &str, id_col: &str, columns: &[&str]) -> String {
+    let set = columns
+        .iter()
+        .map(|col| format!("{col}=EXCLUDED.{col}", col = col))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let columns = columns.join(", ");
+
+    format!(
+        "INSERT INTO {table} ({id}, {columns}, {version}) SELECT {id}, {columns}, {version} + 1 FROM jsonb_populate_record(NULL::{table}, $1)
+        ON CONFLICT ({id}) DO UPDATE SET {set}, {version}={table}.{version} + 1",
+        table = table,
+        id = id_col,
+        columns = columns,
+        set = set,
+        version = VERSION_COL
+    )
+}
+
 /// Group item in HashMap like: [(id_gesture, items)] and others (that are non linked to gesture) are partitioned next to it
 fn group_by_id_gesture<T: GestureReliant>(items: Vec<T>) -> (LinkedHashMap<Uuid, Vec<T>>, Vec<T>) {
     let (some, none): (Vec<T>, Vec<T>) = items
@@ -536,6 +2077,37 @@ fn merge(
 mod tests {
     use super::*;
 
+    #[cfg(test)]
+    mod parse_id {
+        use super::*;
+
+        #[test]
+        fn should_parse_uuid() {
+            assert_eq!(
+                Ok(id_g1()),
+                parse_id("ec953c9e-a1c1-4095-a038-4ea0d2e8efd7")
+            );
+        }
+
+        #[test]
+        fn should_reject_invalid_uuid() {
+            assert_eq!(
+                Err(DbError::InvalidId("not-an-id".to_owned())),
+                parse_id("not-an-id")
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod escape_like {
+        use super::*;
+
+        #[test]
+        fn should_escape_like_wildcards() {
+            assert_eq!("100\\% b\\_a\\\\", escape_like("100% b_a\\"));
+        }
+    }
+
     #[cfg(test)]
     mod group_by_id_gesture {
         use super::*;
@@ -702,6 +2274,79 @@ mod tests {
             );
         }
     }
+
+    #[cfg(test)]
+    mod category_tree {
+        use super::*;
+
+        fn raw_category(id: &str, id_parent: Option<&str>, position: i32) -> RawCategory {
+            RawCategory {
+                id_category: Uuid::parse_str(id).unwrap(),
+                id_parent: id_parent.map(|id| Uuid::parse_str(id).unwrap()),
+                position,
+            }
+        }
+
+        #[test]
+        fn categories_should_be_nested_below_their_parent_in_order() {
+            let root = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01";
+            let first = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a02";
+            let second = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a03";
+            let other_root = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a04";
+
+            let tree = category_tree(
+                vec![
+                    raw_category(root, None, 0),
+                    raw_category(first, Some(root), 0),
+                    raw_category(other_root, None, 1),
+                    raw_category(second, Some(root), 1),
+                ],
+                vec![RawCategoryLabel {
+                    id_category: Uuid::parse_str(root).unwrap(),
+                    lang: "fr".to_owned(),
+                    label: "Salutations".to_owned(),
+                }],
+            );
+
+            let category = |id: &str, id_parent: Option<&str>, position, children| Category {
+                id: id.to_owned(),
+                id_parent: id_parent.map(str::to_owned),
+                position,
+                labels: BTreeMap::new(),
+                children,
+            };
+            let mut root_category = category(
+                root,
+                None,
+                0,
+                vec![
+                    category(first, Some(root), 0, vec![]),
+                    category(second, Some(root), 1, vec![]),
+                ],
+            );
+            root_category
+                .labels
+                .insert("fr".to_owned(), "Salutations".to_owned());
+            assert_eq!(
+                vec![root_category, category(other_root, None, 1, vec![])],
+                tree
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod revert_query {
+        use super::*;
+
+        #[test]
+        fn should_upsert_every_restored_column() {
+            assert_eq!(
+                "INSERT INTO descriptions (id_description, id_gesture, val, version) SELECT id_description, id_gesture, val, version + 1 FROM jsonb_populate_record(NULL::descriptions, $1)
+        ON CONFLICT (id_description) DO UPDATE SET id_gesture=EXCLUDED.id_gesture, val=EXCLUDED.val, version=descriptions.version + 1",
+                revert_query(D_TABLE, ID_D_COL, &[ID_G_COL, VALUE_D_COL])
+            );
+        }
+    }
     // --------------------
     // making data for test
     // --------------------
@@ -713,10 +2358,14 @@ mod tests {
     ) -> Gesture {
         Gesture {
             id: id_g1().to_hyphenated().to_string(),
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
             descriptions,
             meanings,
             pictures,
+            categories: vec![],
+            related: vec![],
+            search_match: None,
         }
     }
 
@@ -727,101 +2376,145 @@ mod tests {
     ) -> Gesture {
         Gesture {
             id: id_g2().to_hyphenated().to_string(),
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
             descriptions,
             meanings,
             pictures,
+            categories: vec![],
+            related: vec![],
+            search_match: None,
         }
     }
 
     fn d1(meanings: Vec<Meaning>) -> Description {
         Description {
             id: id_d1().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valued1".to_owned(),
             meanings,
+            translation_group: None,
+            source: None,
         }
     }
 
     fn d2(meanings: Vec<Meaning>) -> Description {
         Description {
             id: id_d2().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valued2".to_owned(),
             meanings,
+            translation_group: None,
+            source: None,
         }
     }
 
     fn d3(meanings: Vec<Meaning>) -> Description {
         Description {
             id: id_d3().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valued3".to_owned(),
             meanings,
+            translation_group: None,
+            source: None,
         }
     }
 
     fn m1() -> Meaning {
         Meaning {
             id: id_m1().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valuem1".to_owned(),
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
         }
     }
 
     fn m2() -> Meaning {
         Meaning {
             id: id_m2().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valuem2".to_owned(),
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
         }
     }
 
     fn m3() -> Meaning {
         Meaning {
             id: id_m3().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valuem3".to_owned(),
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
         }
     }
 
     fn m4() -> Meaning {
         Meaning {
             id: id_m4().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valuem4".to_owned(),
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
         }
     }
 
     fn m5() -> Meaning {
         Meaning {
             id: id_m5().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             value: "valuem5".to_owned(),
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
         }
     }
 
     fn p1() -> Picture {
         Picture {
             id: id_p1().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            source: None,
         }
     }
 
     fn p2() -> Picture {
         Picture {
             id: id_p2().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            source: None,
         }
     }
 
     fn p3() -> Picture {
         Picture {
             id: id_p3().to_hyphenated().to_string(),
+            version: 1,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            source: None,
         }
     }
 
@@ -829,6 +2522,7 @@ mod tests {
         RawGesture {
             id_gesture: id_g1(),
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            version: 1,
         }
     }
 
@@ -836,6 +2530,7 @@ mod tests {
         RawGesture {
             id_gesture: id_g2(),
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            version: 1,
         }
     }
 
@@ -845,6 +2540,13 @@ mod tests {
             id_gesture: id_g1(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valued1".to_owned(),
+            version: 1,
+            translation_group: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -854,6 +2556,13 @@ mod tests {
             id_gesture: id_g1(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valued2".to_owned(),
+            version: 1,
+            translation_group: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -863,6 +2572,13 @@ mod tests {
             id_gesture: id_g2(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valued3".to_owned(),
+            version: 1,
+            translation_group: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -873,6 +2589,15 @@ mod tests {
             id_description: None,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem1".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -883,6 +2608,15 @@ mod tests {
             id_description: None,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem2".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -893,6 +2627,15 @@ mod tests {
             id_description: None,
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem3".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -903,6 +2646,15 @@ mod tests {
             id_description: Some(id_d1()),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem4".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -913,6 +2665,15 @@ mod tests {
             id_description: Some(id_d3()),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem5".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -923,6 +2684,15 @@ mod tests {
             id_description: Some(id_d3()),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             val: "valuem6".to_owned(),
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -932,6 +2702,12 @@ mod tests {
             id_gesture: id_g1(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            version: 1,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -941,6 +2717,12 @@ mod tests {
             id_gesture: id_g1(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            version: 1,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -950,6 +2732,12 @@ mod tests {
             id_gesture: id_g2(),
             langs: vec!["lang1".to_owned(), "lang2".to_owned()],
             format: "png".to_owned(),
+            version: 1,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
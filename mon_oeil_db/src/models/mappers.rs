@@ -1,11 +1,16 @@
 use super::*;
+use crate::normalize_tags;
 use uuid::Uuid;
 
 impl RawGesture {
     pub fn from(new: NewGesture, id_gesture: Uuid) -> Self {
         let NewGesture { tags, .. } = new;
 
-        Self { id_gesture, tags }
+        Self {
+            id_gesture,
+            tags: normalize_tags(tags),
+            version: 1,
+        }
     }
 }
 
@@ -18,6 +23,13 @@ impl RawDescription {
             id_gesture,
             val: value,
             langs,
+            translation_group: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
+            version: 1,
         }
     }
 }
@@ -29,7 +41,12 @@ impl RawMeaning {
         id_description: Option<Uuid>,
         id_meaning: Uuid,
     ) -> Self {
-        let NewMeaning { value, langs, .. } = new;
+        let NewMeaning {
+            value,
+            langs,
+            regions,
+            register,
+        } = new;
 
         Self {
             id_description,
@@ -37,6 +54,15 @@ impl RawMeaning {
             id_meaning,
             val: value,
             langs,
+            translation_group: None,
+            regions,
+            register,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
+            version: 1,
         }
     }
 }
@@ -50,6 +76,12 @@ impl RawPicture {
             id_gesture,
             langs,
             format,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
+            version: 1,
         }
     }
 }
@@ -58,7 +90,10 @@ impl InnerGesture {
     pub fn from(new: NewGesture, id_gesture: Uuid) -> Self {
         let NewGesture { tags } = new;
 
-        Self { id_gesture, tags }
+        Self {
+            id_gesture,
+            tags: normalize_tags(tags),
+        }
     }
 }
 
@@ -76,12 +111,19 @@ impl InnerDescription {
 
 impl InnerMeaning {
     pub fn from(new: NewMeaning, id_meaning: Uuid) -> Self {
-        let NewMeaning { value, langs, .. } = new;
+        let NewMeaning {
+            value,
+            langs,
+            regions,
+            register,
+        } = new;
 
         Self {
             id_meaning,
             val: value,
             langs,
+            regions,
+            register,
         }
     }
 }
@@ -109,13 +151,21 @@ impl Gesture {
         meanings: Vec<Meaning>,
         pictures: Vec<Picture>,
     ) -> Self {
-        let RawGesture { id_gesture, tags } = raw;
+        let RawGesture {
+            id_gesture,
+            tags,
+            version,
+        } = raw;
         Self {
             id: format!("{}", id_gesture),
+            version,
             tags,
             descriptions,
             meanings,
             pictures,
+            categories: vec![],
+            related: vec![],
+            search_match: None,
         }
     }
 }
@@ -126,13 +176,29 @@ impl Description {
             id_description,
             val,
             langs,
+            translation_group,
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            version,
             ..
         } = raw;
         Self {
             id: format!("{}", id_description),
+            version,
             value: val,
             langs,
             meanings,
+            translation_group: translation_group.map(|id| format!("{}", id)),
+            source: Source::from_raw(RawSource {
+                source,
+                source_page,
+                source_reference,
+                attribution,
+                license,
+            }),
         }
     }
 }
@@ -143,12 +209,32 @@ impl Meaning {
             id_meaning,
             val,
             langs,
+            translation_group,
+            regions,
+            register,
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            version,
             ..
         } = raw;
         Self {
             id: format!("{}", id_meaning),
+            version,
             value: val,
             langs,
+            translation_group: translation_group.map(|id| format!("{}", id)),
+            regions,
+            register,
+            source: Source::from_raw(RawSource {
+                source,
+                source_page,
+                source_reference,
+                attribution,
+                license,
+            }),
         }
     }
 }
@@ -159,12 +245,129 @@ impl Picture {
             id_picture,
             langs,
             format,
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            version,
             ..
         } = raw;
         Self {
             id: format!("{}", id_picture),
+            version,
             langs,
             format,
+            source: Source::from_raw(RawSource {
+                source,
+                source_page,
+                source_reference,
+                attribution,
+                license,
+            }),
+        }
+    }
+}
+
+impl RawDescription {
+    /// The row with the columns of the source
+    pub fn with_source(self, source: Option<Source>) -> Self {
+        let RawSource {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+        } = Source::into_raw(source);
+        Self {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            ..self
+        }
+    }
+}
+
+impl RawMeaning {
+    /// The row with the columns of the source
+    pub fn with_source(self, source: Option<Source>) -> Self {
+        let RawSource {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+        } = Source::into_raw(source);
+        Self {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            ..self
+        }
+    }
+}
+
+impl RawPicture {
+    /// The row with the columns of the source
+    pub fn with_source(self, source: Option<Source>) -> Self {
+        let RawSource {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+        } = Source::into_raw(source);
+        Self {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+            ..self
+        }
+    }
+}
+
+impl Source {
+    /// None when no source kind is set
+    pub fn from_raw(raw: RawSource) -> Option<Self> {
+        let RawSource {
+            source,
+            source_page,
+            source_reference,
+            attribution,
+            license,
+        } = raw;
+        source.map(|kind| Self {
+            kind,
+            page: source_page,
+            reference: source_reference,
+            attribution,
+            license,
+        })
+    }
+
+    /// Columns of a source, all NULL without source
+    pub fn into_raw(source: Option<Self>) -> RawSource {
+        match source {
+            Some(Self {
+                kind,
+                page,
+                reference,
+                attribution,
+                license,
+            }) => RawSource {
+                source: Some(kind),
+                source_page: page,
+                source_reference: reference,
+                attribution,
+                license,
+            },
+            _ => RawSource::default(),
         }
     }
 }
@@ -177,3 +380,95 @@ impl User {
         Self { username, password }
     }
 }
+
+impl Revision {
+    pub fn from_raw(raw: RawRevision) -> Self {
+        let RawRevision {
+            id_history,
+            entity,
+            id_entity,
+            id_gesture,
+            action,
+            before,
+            after,
+            username,
+            creation_date,
+        } = raw;
+        Self {
+            id: id_history,
+            entity,
+            id_entity: format!("{}", id_entity),
+            id_gesture: id_gesture.map(|id| format!("{}", id)),
+            action,
+            before,
+            after,
+            username,
+            date: creation_date,
+        }
+    }
+}
+
+impl Suggestion {
+    pub fn from_raw(raw: RawSuggestion) -> Self {
+        let RawSuggestion { kind, term, count } = raw;
+        Self {
+            kind,
+            value: term,
+            count,
+        }
+    }
+}
+
+impl MissingTranslation {
+    pub fn from_raw(raw: RawTranslatable) -> Self {
+        let RawTranslatable {
+            kind,
+            id,
+            id_gesture,
+            val,
+            langs,
+        } = raw;
+        Self {
+            kind,
+            id: format!("{}", id),
+            id_gesture: format!("{}", id_gesture),
+            value: val,
+            langs,
+        }
+    }
+}
+
+impl Category {
+    pub fn from_raw(
+        raw: RawCategory,
+        labels: BTreeMap<String, String>,
+        children: Vec<Category>,
+    ) -> Self {
+        let RawCategory {
+            id_category,
+            id_parent,
+            position,
+        } = raw;
+        Self {
+            id: format!("{}", id_category),
+            id_parent: id_parent.map(|id| format!("{}", id)),
+            position,
+            labels,
+            children,
+        }
+    }
+}
+
+impl Language {
+    pub fn from_raw(raw: RawLanguage) -> Self {
+        let RawLanguage { code, name } = raw;
+        Self { code, name }
+    }
+}
+
+impl From<Language> for RawLanguage {
+    fn from(language: Language) -> Self {
+        let Language { code, name } = language;
+        Self { code, name }
+    }
+}
@@ -2,46 +2,253 @@ pub(crate) mod raw;
 
 mod mappers;
 
+use chrono::NaiveDateTime;
 use raw::*;
+use serde_json::Value;
 use std::cmp::{Eq, PartialEq};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::DbError;
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Gesture {
     pub id: String,
+    pub version: i32,
     pub tags: Vec<String>,
     pub descriptions: Vec<Description>,
     pub meanings: Vec<Meaning>,
     pub pictures: Vec<Picture>,
+    /// Ids of the categories the gesture belongs to
+    pub categories: Vec<String>,
+    /// Gestures linked to this one, oldest link first
+    pub related: Vec<RelatedGesture>,
+    /// Why the gesture was found, only set on search results
+    pub search_match: Option<SearchMatch>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct SearchMatch {
+    /// Kinds of text the search matched: tag, description or meaning
+    pub matched_in: Vec<String>,
+    /// HTML of the best matching description or meaning, its text escaped and matched words in <mark>
+    pub snippet: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Description {
     pub id: String,
+    pub version: i32,
     pub value: String,
     pub langs: Vec<String>,
     pub meanings: Vec<Meaning>,
+    /// Shared by the descriptions of the gesture saying the same thing in other langs
+    pub translation_group: Option<String>,
+    pub source: Option<Source>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Meaning {
     pub id: String,
+    pub version: i32,
     pub value: String,
     pub langs: Vec<String>,
+    /// Shared by the meanings of the gesture saying the same thing in other langs
+    pub translation_group: Option<String>,
+    /// Country codes where the meaning holds, empty when it holds everywhere
+    pub regions: Vec<String>,
+    /// One of `REGISTERS`, None when unknown
+    pub register: Option<String>,
+    pub source: Option<Source>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Picture {
     pub id: String,
+    pub version: i32,
     pub langs: Vec<String>,
     pub format: String,
+    pub source: Option<Source>,
+}
+
+/// Where a description, meaning or picture comes from
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Source {
+    /// One of `SOURCE_KINDS`
+    pub kind: String,
+    /// Page of the book, only for a book source
+    pub page: Option<i32>,
+    /// Citation or url of an external reference
+    pub reference: Option<String>,
+    /// Author or contributor to credit
+    pub attribution: Option<String>,
+    pub license: Option<String>,
 }
 
+/// Kinds of source: the original book, a contributor or an external reference
+pub const SOURCE_KINDS: &[&str] = &["book", "contributor", "external"];
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct User {
     pub username: String,
     pub password: String,
 }
 
+/// Snapshot of an entity before and after a modification
+#[derive(PartialEq, Eq, Debug)]
+pub struct Revision {
+    pub id: i64,
+    pub entity: String,
+    pub id_entity: String,
+    pub id_gesture: Option<String>,
+    pub action: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub username: Option<String>,
+    pub date: NaiveDateTime,
+}
+
+/// A tag or meaning phrase proposed while typing a search
+#[derive(PartialEq, Eq, Debug)]
+pub struct Suggestion {
+    /// tag or meaning
+    pub kind: String,
+    pub value: String,
+    /// Number of gestures with this tag or meanings with this phrase
+    pub count: i32,
+}
+
+/// A chapter of the book with its subcategories, siblings are ordered by position
+#[derive(PartialEq, Eq, Debug)]
+pub struct Category {
+    pub id: String,
+    pub id_parent: Option<String>,
+    pub position: i32,
+    /// Label of the category by lang
+    pub labels: BTreeMap<String, String>,
+    pub children: Vec<Category>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct NewCategory {
+    /// None for a top level category
+    pub id_parent: Option<String>,
+    pub position: i32,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// A tag and its number of gestures
+#[derive(PartialEq, Eq, Debug)]
+pub struct Tag {
+    pub name: String,
+    pub count: i64,
+}
+
+/// Registers of a meaning, from the most neutral to the most offensive
+pub const REGISTERS: &[&str] = &["neutral", "familiar", "vulgar", "offensive"];
+
+/// Kind of content that can be the translation of another of the same kind
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Translatable {
+    Description,
+    Meaning,
+}
+
+impl Translatable {
+    /// Table of the kind and its id column
+    pub(crate) fn table(self) -> (&'static str, &'static str) {
+        match self {
+            Translatable::Description => (D_TABLE, ID_D_COL),
+            Translatable::Meaning => (M_TABLE, ID_M_COL),
+        }
+    }
+
+    /// Kind in the translatable view
+    pub(crate) fn kind(self) -> &'static str {
+        match self {
+            Translatable::Description => "description",
+            Translatable::Meaning => "meaning",
+        }
+    }
+}
+
+/// A gesture linked to another one
+#[derive(PartialEq, Eq, Debug)]
+pub struct RelatedGesture {
+    pub id: String,
+    /// see_also, variant_of, has_variant or opposite_of, read from the gesture holding it
+    pub kind: String,
+}
+
+/// How a gesture is related to another one
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Relation {
+    SeeAlso,
+    /// The gesture is a variant of the related one
+    VariantOf,
+    /// The related gesture is a variant of this one
+    HasVariant,
+    OppositeOf,
+}
+
+impl Relation {
+    /// Kind in the related_gestures view
+    pub(crate) fn kind(self) -> &'static str {
+        match self {
+            Relation::SeeAlso => "see_also",
+            Relation::VariantOf => "variant_of",
+            Relation::HasVariant => "has_variant",
+            Relation::OppositeOf => "opposite_of",
+        }
+    }
+}
+
+/// Kind of content that can have a source
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Content {
+    Description,
+    Meaning,
+    Picture,
+}
+
+impl Content {
+    /// Table of the kind and its id column
+    pub(crate) fn table(self) -> (&'static str, &'static str) {
+        match self {
+            Content::Description => (D_TABLE, ID_D_COL),
+            Content::Meaning => (M_TABLE, ID_M_COL),
+            Content::Picture => (P_TABLE, ID_P_COL),
+        }
+    }
+}
+
+/// A description or meaning without translation in a lang
+#[derive(PartialEq, Eq, Debug)]
+pub struct MissingTranslation {
+    /// description or meaning
+    pub kind: String,
+    pub id: String,
+    pub id_gesture: String,
+    pub value: String,
+    pub langs: Vec<String>,
+}
+
+/// A page of missing translations and their count over every page
+#[derive(PartialEq, Eq, Debug)]
+pub struct MissingTranslations {
+    pub items: Vec<MissingTranslation>,
+    pub total: i64,
+}
+
+/// A language content can be written in, `code` is a BCP 47 tag like fr or pt-BR
+#[derive(PartialEq, Eq, Debug)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct NewGesture {
     pub tags: Vec<String>,
@@ -57,6 +264,8 @@ pub struct NewDescription {
 pub struct NewMeaning {
     pub value: String,
     pub langs: Vec<String>,
+    pub regions: Vec<String>,
+    pub register: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -75,8 +284,174 @@ pub struct NewPictureFileInfo {
     pub format: String,
 }
 
+/// A gesture and all its content, added at once by an import
+#[derive(PartialEq, Eq, Debug)]
+pub struct NewGestureTree {
+    pub gesture: NewGesture,
+    pub descriptions: Vec<(NewDescription, Vec<NewMeaning>)>,
+    pub meanings: Vec<NewMeaning>,
+    /// Pictures with the ids of their files, uploaded before the import
+    pub pictures: Vec<(String, NewPicture)>,
+}
+
+/// Ids given to an imported gesture and its pictures, in the order of the import
 #[derive(PartialEq, Eq, Debug)]
-pub struct PaginationRequest {
-    pub max: u16,
-    pub page: u16,
+pub struct ImportedGesture {
+    pub id: String,
+    pub pictures: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum PaginationRequest {
+    /// `max` gestures of a page starting at 1
+    Page { max: u16, page: u32 },
+    /// `max` gestures listed after the cursor, from the first one without cursor
+    Keyset { max: u16, after: Option<Cursor> },
+}
+
+impl PaginationRequest {
+    pub fn max(&self) -> u16 {
+        match self {
+            PaginationRequest::Page { max, .. } | PaginationRequest::Keyset { max, .. } => *max,
+        }
+    }
+}
+
+/// Position of a gesture in the listing by creation date, newest first
+/// Shared as `<creation timestamp in microseconds>_<id>`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Cursor {
+    pub creation_date: NaiveDateTime,
+    pub id: Uuid,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let micros = self.creation_date.timestamp() * 1_000_000
+            + i64::from(self.creation_date.timestamp_subsec_micros());
+        write!(f, "{}_{}", micros, self.id.to_hyphenated())
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = DbError;
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        let invalid = || DbError::InvalidCursor(cursor.to_owned());
+        let mut parts = cursor.splitn(2, '_');
+        let micros = parts
+            .next()
+            .and_then(|micros| micros.parse::<i64>().ok())
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+        let creation_date = NaiveDateTime::from_timestamp_opt(
+            micros.div_euclid(1_000_000),
+            (micros.rem_euclid(1_000_000) * 1000) as u32,
+        )
+        .ok_or_else(invalid)?;
+
+        Ok(Self { creation_date, id })
+    }
+}
+
+/// A page of gestures with the total and facets of every page
+#[derive(PartialEq, Eq, Debug)]
+pub struct GesturesPage {
+    pub gestures: Vec<Gesture>,
+    pub total: i64,
+    pub facets: Option<Facets>,
+    /// Cursor of the last gesture when more gestures follow, keyset pagination only
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SearchRequest {
+    pub text: String,
+    /// Only search texts in this lang, ranked with its dictionary
+    pub lang: Option<String>,
+}
+
+/// Order of listed gestures, ties are broken by id so pages don't overlap
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Sort {
+    /// Best ranked first when searching, newest otherwise
+    Relevance,
+    Newest,
+    Oldest,
+    /// Last modified gesture or content first, from the history
+    LastUpdated,
+    /// Alphabetical by first tag, accents and case are ignored
+    Tag,
+    /// Alphabetical by first meaning in the lang, in any lang without it
+    Meaning {
+        lang: Option<String>,
+    },
+    /// Same order for the same seed, a new order on every query without it
+    Random {
+        seed: Option<String>,
+    },
+}
+
+impl Default for Sort {
+    fn default() -> Self {
+        Sort::Relevance
+    }
+}
+
+/// Number of matching gestures for each tag and lang, most frequent first
+#[derive(PartialEq, Eq, Debug)]
+pub struct Facets {
+    pub tags: Vec<(String, i64)>,
+    pub langs: Vec<(String, i64)>,
+}
+
+/// Gestures to keep, every filter set has to match
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct FilterRequest {
+    /// Gestures having all these tags
+    pub tags: Vec<String>,
+    /// Gestures with a description, meaning or picture in this lang
+    pub lang: Option<String>,
+    pub has_pictures: Option<bool>,
+    pub created_after: Option<NaiveDateTime>,
+    /// Id of a category, gestures in it or in one of its subcategories
+    pub category: Option<String>,
+    /// Gestures with a meaning holding in this country
+    pub region: Option<String>,
+    /// Gestures with a meaning of this register, in the region when it is set too
+    pub register: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_should_round_trip() {
+        let cursor = Cursor {
+            creation_date: NaiveDateTime::from_timestamp(1_603_040_000, 123_456_000),
+            id: Uuid::parse_str("ce27c124-e47b-490f-b8fe-3f37d5dbbef6").unwrap(),
+        };
+
+        assert_eq!(
+            "1603040000123456_ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+            cursor.to_string()
+        );
+        assert_eq!(Ok(cursor.clone()), cursor.to_string().parse());
+    }
+
+    #[test]
+    fn cursor_should_reject_garbage() {
+        assert_eq!(
+            Err(DbError::InvalidCursor("abc".to_owned())),
+            "abc".parse::<Cursor>()
+        );
+        assert_eq!(
+            Err(DbError::InvalidCursor("12_abc".to_owned())),
+            "12_abc".parse::<Cursor>()
+        );
+    }
 }
@@ -1,6 +1,9 @@
 use crate::*;
+use chrono::NaiveDateTime;
+use serde_json::Value;
 use std::cmp::{Eq, PartialEq};
 use tokio_pg_mapper_derive::PostgresMapper;
+use tokio_postgres::types::{FromSql, Type};
 use uuid::Uuid;
 
 /// Has a direct link to a gesture (id_gesture should be retrievable)
@@ -13,8 +16,19 @@ pub const D_TABLE: &str = "descriptions";
 pub const M_TABLE: &str = "meanings";
 pub const M_TABLE_WITH_G_ID: &str = "meanings_with_gesture_id";
 pub const SEARCHABLE_VIEW: &str = "searchable";
+pub const FUZZY_SEARCHABLE_VIEW: &str = "fuzzy_searchable";
+pub const GESTURE_LANGS_VIEW: &str = "gesture_langs";
+pub const TRANSLATABLE_VIEW: &str = "translatable";
 pub const P_TABLE: &str = "pictures";
 pub const U_TABLE: &str = "users";
+pub const H_TABLE: &str = "history";
+pub const S_TABLE: &str = "suggestions";
+pub const L_TABLE: &str = "languages";
+pub const C_TABLE: &str = "categories";
+pub const CL_TABLE: &str = "category_labels";
+pub const GC_TABLE: &str = "gesture_categories";
+pub const GL_TABLE: &str = "gesture_links";
+pub const RELATED_VIEW: &str = "related_gestures";
 
 pub const ID_G_COL: &str = "id_gesture";
 pub const ID_DG_COL: &str = "id_description_gesture";
@@ -26,12 +40,57 @@ pub const LANG_D_COL: &str = "langs";
 pub const ID_M_COL: &str = "id_meaning";
 pub const VALUE_M_COL: &str = "val";
 pub const LANG_M_COL: &str = "langs";
+pub const REGIONS_M_COL: &str = "regions";
+pub const REGISTER_M_COL: &str = "register";
 pub const ID_P_COL: &str = "id_picture";
 pub const LANG_P_COL: &str = "langs";
 pub const FORMAT_P_COL: &str = "format";
+pub const TRANSLATION_GROUP_COL: &str = "translation_group";
+pub const ID_T_COL: &str = "id";
+pub const KIND_T_COL: &str = "kind";
 pub const USERNAME_COL: &str = "username";
-pub const _PASSWORD_COL: &str = "password";
+pub const PASSWORD_COL: &str = "password";
 pub const DOCUMENT: &str = "document";
+pub const LANG_S_COL: &str = "lang";
+pub const KIND_S_COL: &str = "kind";
+pub const NORMALIZED_S_COL: &str = "normalized";
+pub const ID_H_COL: &str = "id_history";
+pub const ENTITY_H_COL: &str = "entity";
+pub const ID_ENTITY_H_COL: &str = "id_entity";
+pub const ACTION_H_COL: &str = "action";
+pub const BEFORE_H_COL: &str = "before";
+pub const AFTER_H_COL: &str = "after";
+pub const VERSION_COL: &str = "version";
+pub const KIND_SU_COL: &str = "kind";
+pub const TERM_SU_COL: &str = "term";
+pub const NORMALIZED_SU_COL: &str = "normalized";
+pub const COUNT_SU_COL: &str = "count";
+pub const CODE_L_COL: &str = "code";
+pub const NAME_L_COL: &str = "name";
+pub const ID_C_COL: &str = "id_category";
+pub const ID_PARENT_C_COL: &str = "id_parent";
+pub const POSITION_C_COL: &str = "position";
+pub const LANG_CL_COL: &str = "lang";
+pub const LABEL_CL_COL: &str = "label";
+pub const ID_RELATED_COL: &str = "id_related";
+pub const KIND_GL_COL: &str = "kind";
+pub const SOURCE_COL: &str = "source";
+pub const SOURCE_PAGE_COL: &str = "source_page";
+pub const SOURCE_REFERENCE_COL: &str = "source_reference";
+pub const ATTRIBUTION_COL: &str = "attribution";
+pub const LICENSE_COL: &str = "license";
+
+/// Columns of the source of a description, meaning or picture
+pub const SOURCE_COLUMNS: &[&str] = &[
+    SOURCE_COL,
+    SOURCE_PAGE_COL,
+    SOURCE_REFERENCE_COL,
+    ATTRIBUTION_COL,
+    LICENSE_COL,
+];
+
+/// Transaction setting read by history triggers to know who made a change
+pub const AUTHOR_SETTING: &str = "mon_oeil.username";
 
 pub trait Insertable {
     /// Parametrized insert query
@@ -40,16 +99,105 @@ pub trait Insertable {
 }
 
 pub trait Updatable {
-    /// Parametrized insert query
+    /// Parametrized update query, it increments and returns the version
     fn update_query(&self) -> String;
     fn query_params(&self) -> Vec<&(dyn ToSql + Sync)>;
 }
 
+/// Columns a raw struct is mapped from
+pub trait Columns {
+    /// Table or view it is selected from
+    const TABLE: &'static str;
+    /// Column names with a check that their postgres type can be decoded
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)];
+}
+
+fn accepts<T: FromSql<'static>>(ty: &Type) -> bool {
+    T::accepts(ty)
+}
+
+/// A row inserted with a given creation date and version, like an exported one, the creation
+/// date is now when unknown
+pub struct Dated<T> {
+    pub item: T,
+    pub creation_date: Option<NaiveDateTime>,
+    pub version: i32,
+}
+
+impl<T: Insertable> Insertable for Dated<T> {
+    fn insert_query(&self) -> String {
+        let query = self.item.insert_query();
+        let count = self.item.query_params().len();
+        // the item query ends with its VALUES list
+        format!(
+            "{}, COALESCE(${creation}::timestamp, NOW()), ${version})",
+            query[..query.len() - 1].replacen(
+                ") VALUES (",
+                &format!(", {}, {}) VALUES (", CREATION_COL, VERSION_COL),
+                1
+            ),
+            creation = count + 1,
+            version = count + 2
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        let mut params = self.item.query_params();
+        params.push(&self.creation_date);
+        params.push(&self.version);
+        params
+    }
+}
+
+/// Columns used in queries but not mapped by any raw struct
+pub const UNMAPPED_COLUMNS: &[(&str, &[&str])] = &[
+    (G_TABLE, &[CREATION_COL, DOCUMENT]),
+    (D_TABLE, &[CREATION_COL]),
+    (M_TABLE, &[CREATION_COL]),
+    (M_TABLE_WITH_G_ID, &[ID_DG_COL, CREATION_COL]),
+    (P_TABLE, &[CREATION_COL]),
+    (
+        SEARCHABLE_VIEW,
+        &[ID_G_COL, LANG_S_COL, DOCUMENT, KIND_S_COL, VALUE_D_COL],
+    ),
+    (
+        FUZZY_SEARCHABLE_VIEW,
+        &[ID_G_COL, LANG_S_COL, NORMALIZED_S_COL, KIND_S_COL],
+    ),
+    (GESTURE_LANGS_VIEW, &[ID_G_COL, LANG_S_COL]),
+    (TRANSLATABLE_VIEW, &[TRANSLATION_GROUP_COL]),
+    (S_TABLE, &[NORMALIZED_SU_COL]),
+    (L_TABLE, &[CREATION_COL]),
+    (C_TABLE, &[CREATION_COL]),
+    (GL_TABLE, &[CREATION_COL]),
+    (RELATED_VIEW, &[CREATION_COL]),
+];
+
 #[derive(PartialEq, Eq, Debug, PostgresMapper)]
 #[pg_mapper(table = "gestures")]
 pub struct RawGesture {
     pub id_gesture: Uuid,
     pub tags: Vec<String>,
+    pub version: i32,
+}
+
+impl Columns for RawGesture {
+    const TABLE: &'static str = G_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_G_COL, accepts::<Uuid>),
+        (TAGS_COL, accepts::<Vec<String>>),
+        (VERSION_COL, accepts::<i32>),
+    ];
+}
+
+/// Source columns shared by descriptions, meanings and pictures
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct RawSource {
+    pub source: Option<String>,
+    pub source_page: Option<i32>,
+    pub source_reference: Option<String>,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -74,8 +222,11 @@ impl Insertable for RawGesture {
 impl Updatable for InnerGesture {
     fn update_query(&self) -> String {
         format!(
-            "UPDATE {} SET {}=$1 WHERE {}=$2",
-            G_TABLE, TAGS_COL, ID_G_COL
+            "UPDATE {table} SET {}=$1, {version}={version} + 1 WHERE {}=$2 RETURNING {version}",
+            TAGS_COL,
+            ID_G_COL,
+            table = G_TABLE,
+            version = VERSION_COL
         )
     }
 
@@ -97,13 +248,43 @@ pub struct RawDescription {
     pub id_gesture: Uuid,
     pub val: String,
     pub langs: Vec<String>,
+    pub translation_group: Option<Uuid>,
+    pub source: Option<String>,
+    pub source_page: Option<i32>,
+    pub source_reference: Option<String>,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    pub version: i32,
+}
+
+impl Columns for RawDescription {
+    const TABLE: &'static str = D_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_D_COL, accepts::<Uuid>),
+        (ID_G_COL, accepts::<Uuid>),
+        (VALUE_D_COL, accepts::<String>),
+        (LANG_D_COL, accepts::<Vec<String>>),
+        (TRANSLATION_GROUP_COL, accepts::<Option<Uuid>>),
+        (SOURCE_COL, accepts::<Option<String>>),
+        (SOURCE_PAGE_COL, accepts::<Option<i32>>),
+        (SOURCE_REFERENCE_COL, accepts::<Option<String>>),
+        (ATTRIBUTION_COL, accepts::<Option<String>>),
+        (LICENSE_COL, accepts::<Option<String>>),
+        (VERSION_COL, accepts::<i32>),
+    ];
 }
 
 impl Insertable for RawDescription {
     fn insert_query(&self) -> String {
         format!(
-            "INSERT INTO {} ({}, {}, {}, {}) VALUES ($1, $2, $3, $4)",
-            D_TABLE, ID_D_COL, ID_G_COL, VALUE_D_COL, LANG_D_COL
+            "INSERT INTO {} ({}, {}, {}, {}, {}, {}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            D_TABLE,
+            ID_D_COL,
+            ID_G_COL,
+            VALUE_D_COL,
+            LANG_D_COL,
+            TRANSLATION_GROUP_COL,
+            SOURCE_COLUMNS.join(", ")
         )
     }
 
@@ -113,6 +294,12 @@ impl Insertable for RawDescription {
             &self.id_gesture,
             &self.val,
             &self.langs,
+            &self.translation_group,
+            &self.source,
+            &self.source_page,
+            &self.source_reference,
+            &self.attribution,
+            &self.license,
         ]
     }
 }
@@ -127,8 +314,12 @@ pub struct InnerDescription {
 impl Updatable for InnerDescription {
     fn update_query(&self) -> String {
         format!(
-            "UPDATE {} SET {}=$1, {}=$2 WHERE {}=$3",
-            D_TABLE, VALUE_D_COL, LANG_D_COL, ID_D_COL
+            "UPDATE {table} SET {}=$1, {}=$2, {version}={version} + 1 WHERE {}=$3 RETURNING {version}",
+            VALUE_D_COL,
+            LANG_D_COL,
+            ID_D_COL,
+            table = D_TABLE,
+            version = VERSION_COL
         )
     }
 
@@ -151,12 +342,41 @@ pub struct RawMeaning {
     pub id_description: Option<Uuid>,
     pub val: String,
     pub langs: Vec<String>,
+    pub translation_group: Option<Uuid>,
+    pub regions: Vec<String>,
+    pub register: Option<String>,
+    pub source: Option<String>,
+    pub source_page: Option<i32>,
+    pub source_reference: Option<String>,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    pub version: i32,
+}
+
+impl Columns for RawMeaning {
+    const TABLE: &'static str = M_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_M_COL, accepts::<Uuid>),
+        (ID_G_COL, accepts::<Option<Uuid>>),
+        (ID_D_COL, accepts::<Option<Uuid>>),
+        (VALUE_M_COL, accepts::<String>),
+        (LANG_M_COL, accepts::<Vec<String>>),
+        (TRANSLATION_GROUP_COL, accepts::<Option<Uuid>>),
+        (REGIONS_M_COL, accepts::<Vec<String>>),
+        (REGISTER_M_COL, accepts::<Option<String>>),
+        (SOURCE_COL, accepts::<Option<String>>),
+        (SOURCE_PAGE_COL, accepts::<Option<i32>>),
+        (SOURCE_REFERENCE_COL, accepts::<Option<String>>),
+        (ATTRIBUTION_COL, accepts::<Option<String>>),
+        (LICENSE_COL, accepts::<Option<String>>),
+        (VERSION_COL, accepts::<i32>),
+    ];
 }
 
 impl Insertable for RawMeaning {
     fn insert_query(&self) -> String {
         format!(
-            "INSERT INTO {} ({}, {}, {}, {}) VALUES ($1, $2, $3, $4)",
+            "INSERT INTO {} ({}, {}, {}, {}, {}, {}, {}, {}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
             M_TABLE,
             ID_M_COL,
             match self.id_gesture {
@@ -164,7 +384,11 @@ impl Insertable for RawMeaning {
                 _ => ID_D_COL,
             },
             VALUE_M_COL,
-            LANG_M_COL
+            LANG_M_COL,
+            TRANSLATION_GROUP_COL,
+            REGIONS_M_COL,
+            REGISTER_M_COL,
+            SOURCE_COLUMNS.join(", ")
         )
     }
 
@@ -177,6 +401,14 @@ impl Insertable for RawMeaning {
             },
             &self.val,
             &self.langs,
+            &self.translation_group,
+            &self.regions,
+            &self.register,
+            &self.source,
+            &self.source_page,
+            &self.source_reference,
+            &self.attribution,
+            &self.license,
         ]
     }
 }
@@ -186,18 +418,32 @@ pub struct InnerMeaning {
     pub id_meaning: Uuid,
     pub val: String,
     pub langs: Vec<String>,
+    pub regions: Vec<String>,
+    pub register: Option<String>,
 }
 
 impl Updatable for InnerMeaning {
     fn update_query(&self) -> String {
         format!(
-            "UPDATE {} SET {}=$1, {}=$2 WHERE {}=$3",
-            M_TABLE, VALUE_M_COL, LANG_M_COL, ID_M_COL
+            "UPDATE {table} SET {}=$1, {}=$2, {}=$3, {}=$4, {version}={version} + 1 WHERE {}=$5 RETURNING {version}",
+            VALUE_M_COL,
+            LANG_M_COL,
+            REGIONS_M_COL,
+            REGISTER_M_COL,
+            ID_M_COL,
+            table = M_TABLE,
+            version = VERSION_COL
         )
     }
 
     fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
-        vec![&self.val, &self.langs, &self.id_meaning]
+        vec![
+            &self.val,
+            &self.langs,
+            &self.regions,
+            &self.register,
+            &self.id_meaning,
+        ]
     }
 }
 
@@ -217,13 +463,40 @@ pub struct RawPicture {
     pub id_gesture: Uuid,
     pub langs: Vec<String>,
     pub format: String,
+    pub source: Option<String>,
+    pub source_page: Option<i32>,
+    pub source_reference: Option<String>,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    pub version: i32,
+}
+
+impl Columns for RawPicture {
+    const TABLE: &'static str = P_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_P_COL, accepts::<Uuid>),
+        (ID_G_COL, accepts::<Uuid>),
+        (LANG_P_COL, accepts::<Vec<String>>),
+        (FORMAT_P_COL, accepts::<String>),
+        (SOURCE_COL, accepts::<Option<String>>),
+        (SOURCE_PAGE_COL, accepts::<Option<i32>>),
+        (SOURCE_REFERENCE_COL, accepts::<Option<String>>),
+        (ATTRIBUTION_COL, accepts::<Option<String>>),
+        (LICENSE_COL, accepts::<Option<String>>),
+        (VERSION_COL, accepts::<i32>),
+    ];
 }
 
 impl Insertable for RawPicture {
     fn insert_query(&self) -> String {
         format!(
-            "INSERT INTO {} ({}, {}, {}, {}) VALUES ($1, $2, $3, $4)",
-            P_TABLE, ID_P_COL, ID_G_COL, LANG_P_COL, FORMAT_P_COL
+            "INSERT INTO {} ({}, {}, {}, {}, {}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            P_TABLE,
+            ID_P_COL,
+            ID_G_COL,
+            LANG_P_COL,
+            FORMAT_P_COL,
+            SOURCE_COLUMNS.join(", ")
         )
     }
 
@@ -233,6 +506,11 @@ impl Insertable for RawPicture {
             &self.id_gesture,
             &self.langs,
             &self.format,
+            &self.source,
+            &self.source_page,
+            &self.source_reference,
+            &self.attribution,
+            &self.license,
         ]
     }
 }
@@ -246,8 +524,11 @@ pub struct InnerPictureMeta {
 impl Updatable for InnerPictureMeta {
     fn update_query(&self) -> String {
         format!(
-            "UPDATE {} SET {}=$1 WHERE {}=$2",
-            P_TABLE, LANG_P_COL, ID_P_COL
+            "UPDATE {table} SET {}=$1, {version}={version} + 1 WHERE {}=$2 RETURNING {version}",
+            LANG_P_COL,
+            ID_P_COL,
+            table = P_TABLE,
+            version = VERSION_COL
         )
     }
 
@@ -265,8 +546,11 @@ pub struct PictureFileInfo {
 impl Updatable for PictureFileInfo {
     fn update_query(&self) -> String {
         format!(
-            "UPDATE {} SET {}=$1 WHERE {}=$2",
-            P_TABLE, FORMAT_P_COL, ID_P_COL
+            "UPDATE {table} SET {}=$1, {version}={version} + 1 WHERE {}=$2 RETURNING {version}",
+            FORMAT_P_COL,
+            ID_P_COL,
+            table = P_TABLE,
+            version = VERSION_COL
         )
     }
 
@@ -288,6 +572,315 @@ pub struct RawUser {
     pub password: String,
 }
 
+impl Columns for RawUser {
+    const TABLE: &'static str = U_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (USERNAME_COL, accepts::<String>),
+        (PASSWORD_COL, accepts::<String>),
+    ];
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "history")]
+pub struct RawRevision {
+    pub id_history: i64,
+    pub entity: String,
+    pub id_entity: Uuid,
+    pub id_gesture: Option<Uuid>,
+    pub action: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub username: Option<String>,
+    pub creation_date: NaiveDateTime,
+}
+
+impl Columns for RawRevision {
+    const TABLE: &'static str = H_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_H_COL, accepts::<i64>),
+        (ENTITY_H_COL, accepts::<String>),
+        (ID_ENTITY_H_COL, accepts::<Uuid>),
+        (ID_G_COL, accepts::<Option<Uuid>>),
+        (ACTION_H_COL, accepts::<String>),
+        (BEFORE_H_COL, accepts::<Option<Value>>),
+        (AFTER_H_COL, accepts::<Option<Value>>),
+        (USERNAME_COL, accepts::<Option<String>>),
+        (CREATION_COL, accepts::<NaiveDateTime>),
+    ];
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "suggestions")]
+pub struct RawSuggestion {
+    pub kind: String,
+    pub term: String,
+    pub count: i32,
+}
+
+impl Columns for RawSuggestion {
+    const TABLE: &'static str = S_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (KIND_SU_COL, accepts::<String>),
+        (TERM_SU_COL, accepts::<String>),
+        (COUNT_SU_COL, accepts::<i32>),
+    ];
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "translatable")]
+pub struct RawTranslatable {
+    pub kind: String,
+    pub id: Uuid,
+    pub id_gesture: Uuid,
+    pub val: String,
+    pub langs: Vec<String>,
+}
+
+impl Columns for RawTranslatable {
+    const TABLE: &'static str = TRANSLATABLE_VIEW;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (KIND_T_COL, accepts::<String>),
+        (ID_T_COL, accepts::<Uuid>),
+        (ID_G_COL, accepts::<Uuid>),
+        (VALUE_D_COL, accepts::<String>),
+        (LANG_D_COL, accepts::<Vec<String>>),
+    ];
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "languages")]
+pub struct RawLanguage {
+    pub code: String,
+    pub name: String,
+}
+
+impl Columns for RawLanguage {
+    const TABLE: &'static str = L_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (CODE_L_COL, accepts::<String>),
+        (NAME_L_COL, accepts::<String>),
+    ];
+}
+
+impl Insertable for RawLanguage {
+    fn insert_query(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}) VALUES ($1, $2)",
+            L_TABLE, CODE_L_COL, NAME_L_COL
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        vec![&self.code, &self.name]
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "categories")]
+pub struct RawCategory {
+    pub id_category: Uuid,
+    pub id_parent: Option<Uuid>,
+    pub position: i32,
+}
+
+impl Columns for RawCategory {
+    const TABLE: &'static str = C_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_C_COL, accepts::<Uuid>),
+        (ID_PARENT_C_COL, accepts::<Option<Uuid>>),
+        (POSITION_C_COL, accepts::<i32>),
+    ];
+}
+
+impl Insertable for RawCategory {
+    fn insert_query(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}, {}) VALUES ($1, $2, $3)",
+            C_TABLE, ID_C_COL, ID_PARENT_C_COL, POSITION_C_COL
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        vec![&self.id_category, &self.id_parent, &self.position]
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "category_labels")]
+pub struct RawCategoryLabel {
+    pub id_category: Uuid,
+    pub lang: String,
+    pub label: String,
+}
+
+impl Columns for RawCategoryLabel {
+    const TABLE: &'static str = CL_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_C_COL, accepts::<Uuid>),
+        (LANG_CL_COL, accepts::<String>),
+        (LABEL_CL_COL, accepts::<String>),
+    ];
+}
+
+impl Insertable for RawCategoryLabel {
+    fn insert_query(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}, {}) VALUES ($1, $2, $3)",
+            CL_TABLE, ID_C_COL, LANG_CL_COL, LABEL_CL_COL
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        vec![&self.id_category, &self.lang, &self.label]
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "gesture_categories")]
+pub struct RawGestureCategory {
+    pub id_gesture: Uuid,
+    pub id_category: Uuid,
+}
+
+impl GestureReliant for RawGestureCategory {
+    fn id_gesture(&self) -> Option<&Uuid> {
+        Some(&self.id_gesture)
+    }
+}
+
+impl Columns for RawGestureCategory {
+    const TABLE: &'static str = GC_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] =
+        &[(ID_G_COL, accepts::<Uuid>), (ID_C_COL, accepts::<Uuid>)];
+}
+
+impl Insertable for RawGestureCategory {
+    fn insert_query(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}) VALUES ($1, $2)",
+            GC_TABLE, ID_G_COL, ID_C_COL
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        vec![&self.id_gesture, &self.id_category]
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "gesture_links")]
+pub struct RawGestureLink {
+    pub id_gesture: Uuid,
+    pub id_related: Uuid,
+    pub kind: String,
+}
+
+impl Columns for RawGestureLink {
+    const TABLE: &'static str = GL_TABLE;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_G_COL, accepts::<Uuid>),
+        (ID_RELATED_COL, accepts::<Uuid>),
+        (KIND_GL_COL, accepts::<String>),
+    ];
+}
+
+impl Insertable for RawGestureLink {
+    fn insert_query(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}, {}) VALUES ($1, $2, $3)",
+            GL_TABLE, ID_G_COL, ID_RELATED_COL, KIND_GL_COL
+        )
+    }
+
+    fn query_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        vec![&self.id_gesture, &self.id_related, &self.kind]
+    }
+}
+
+/// A link seen from one of its gestures, see the related_gestures view
+#[derive(PartialEq, Eq, Debug, PostgresMapper)]
+#[pg_mapper(table = "related_gestures")]
+pub struct RawRelatedGesture {
+    pub id_gesture: Uuid,
+    pub id_related: Uuid,
+    pub kind: String,
+}
+
+impl GestureReliant for RawRelatedGesture {
+    fn id_gesture(&self) -> Option<&Uuid> {
+        Some(&self.id_gesture)
+    }
+}
+
+impl Columns for RawRelatedGesture {
+    const TABLE: &'static str = RELATED_VIEW;
+    const COLUMNS: &'static [(&'static str, fn(&Type) -> bool)] = &[
+        (ID_G_COL, accepts::<Uuid>),
+        (ID_RELATED_COL, accepts::<Uuid>),
+        (KIND_GL_COL, accepts::<String>),
+    ];
+}
+
+/// Table, id column and columns restored when reverting an entity of history
+pub fn revertable_columns(
+    entity: &str,
+) -> Option<(&'static str, &'static str, &'static [&'static str])> {
+    match entity {
+        G_TABLE => Some((G_TABLE, ID_G_COL, &[TAGS_COL, CREATION_COL])),
+        D_TABLE => Some((
+            D_TABLE,
+            ID_D_COL,
+            &[
+                ID_G_COL,
+                VALUE_D_COL,
+                LANG_D_COL,
+                TRANSLATION_GROUP_COL,
+                SOURCE_COL,
+                SOURCE_PAGE_COL,
+                SOURCE_REFERENCE_COL,
+                ATTRIBUTION_COL,
+                LICENSE_COL,
+                CREATION_COL,
+            ],
+        )),
+        M_TABLE => Some((
+            M_TABLE,
+            ID_M_COL,
+            &[
+                ID_G_COL,
+                ID_D_COL,
+                VALUE_M_COL,
+                LANG_M_COL,
+                TRANSLATION_GROUP_COL,
+                REGIONS_M_COL,
+                REGISTER_M_COL,
+                SOURCE_COL,
+                SOURCE_PAGE_COL,
+                SOURCE_REFERENCE_COL,
+                ATTRIBUTION_COL,
+                LICENSE_COL,
+                CREATION_COL,
+            ],
+        )),
+        P_TABLE => Some((
+            P_TABLE,
+            ID_P_COL,
+            &[
+                ID_G_COL,
+                LANG_P_COL,
+                FORMAT_P_COL,
+                SOURCE_COL,
+                SOURCE_PAGE_COL,
+                SOURCE_REFERENCE_COL,
+                ATTRIBUTION_COL,
+                LICENSE_COL,
+                CREATION_COL,
+            ],
+        )),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,6 +930,8 @@ mod tests {
                     NewMeaning {
                         value: "value".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
+                        regions: vec![],
+                        register: None,
                     },
                     None,
                     id_description,
@@ -355,6 +950,8 @@ mod tests {
                     NewMeaning {
                         value: "value".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
+                        regions: vec![],
+                        register: None,
                     },
                     id_gesture,
                     None,
@@ -385,6 +982,7 @@ mod tests {
         RawGesture {
             id_gesture,
             tags: vec!["ah".to_owned(), "ha".to_owned()],
+            version: 1,
         }
     }
 
@@ -394,6 +992,13 @@ mod tests {
             id_gesture,
             val: "value".to_owned(),
             langs: vec!["fr".to_owned(), "us".to_owned()],
+            version: 1,
+            translation_group: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -408,6 +1013,15 @@ mod tests {
             id_meaning,
             val: "value".to_owned(),
             langs: vec!["fr".to_owned(), "us".to_owned()],
+            version: 1,
+            translation_group: None,
+            regions: vec![],
+            register: None,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 
@@ -417,6 +1031,12 @@ mod tests {
             id_picture,
             langs: vec!["fr".to_owned(), "us".to_owned()],
             format: "png".to_owned(),
+            version: 1,
+            source: None,
+            source_page: None,
+            source_reference: None,
+            attribution: None,
+            license: None,
         }
     }
 }
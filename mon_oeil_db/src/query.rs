@@ -0,0 +1,278 @@
+use tokio_postgres::types::ToSql;
+
+use crate::models::raw::*;
+use crate::models::{FilterRequest, Sort};
+use crate::tags::normalize_tags;
+use crate::{parse_id, DbError};
+
+/// Values of a query built piece by piece, every piece asks for the placeholders of its values
+#[derive(Default)]
+pub struct Params {
+    values: Vec<Box<dyn ToSql + Sync + Send>>,
+}
+
+impl Params {
+    /// Add a value and return its placeholder
+    pub fn push<T: ToSql + Sync + Send + 'static>(&mut self, value: T) -> String {
+        self.values.push(Box::new(value));
+        format!("${}", self.values.len())
+    }
+
+    pub fn refs(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.values
+            .iter()
+            .map(|value| &**value as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+/// Conditions on the gestures table, one for each filter set
+pub fn filter_conditions(
+    filter: FilterRequest,
+    params: &mut Params,
+) -> Result<Vec<String>, DbError> {
+    let FilterRequest {
+        tags,
+        lang,
+        has_pictures,
+        created_after,
+        category,
+        region,
+        register,
+    } = filter;
+    let mut conditions = Vec::new();
+
+    if !tags.is_empty() {
+        conditions.push(format!(
+            "{g_table}.{tags} @> {}::text[]",
+            params.push(normalize_tags(tags)),
+            g_table = G_TABLE,
+            tags = TAGS_COL
+        ));
+    }
+
+    if let Some(lang) = lang {
+        // any text or picture of the gesture is in this lang
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM {gl_view} WHERE {gl_view}.{id_g} = {g_table}.{id_g} AND {gl_view}.{lang} = {})",
+            params.push(lang),
+            g_table = G_TABLE,
+            gl_view = GESTURE_LANGS_VIEW,
+            id_g = ID_G_COL,
+            lang = LANG_S_COL
+        ));
+    }
+
+    if let Some(has_pictures) = has_pictures {
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM {p_table} WHERE {p_table}.{id_g} = {g_table}.{id_g}) = {}",
+            params.push(has_pictures),
+            g_table = G_TABLE,
+            p_table = P_TABLE,
+            id_g = ID_G_COL
+        ));
+    }
+
+    if let Some(created_after) = created_after {
+        conditions.push(format!(
+            "{}.{} > {}",
+            G_TABLE,
+            CREATION_COL,
+            params.push(created_after)
+        ));
+    }
+
+    if let Some(category) = category {
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM {gc_table} WHERE {gc_table}.{id_g} = {g_table}.{id_g}
+            AND {gc_table}.{id_c} IN (SELECT category_subtree({})))",
+            params.push(parse_id(&category)?),
+            g_table = G_TABLE,
+            gc_table = GC_TABLE,
+            id_g = ID_G_COL,
+            id_c = ID_C_COL
+        ));
+    }
+
+    if region.is_some() || register.is_some() {
+        // a single meaning of the gesture matches both
+        let mut meaning_conditions = Vec::new();
+        // a meaning without regions holds everywhere
+        if let Some(region) = region {
+            meaning_conditions.push(format!(
+                " AND (m.{regions} = '{{}}' OR {}::text = ANY(m.{regions}))",
+                params.push(region.to_uppercase()),
+                regions = REGIONS_M_COL
+            ));
+        }
+        if let Some(register) = register {
+            meaning_conditions.push(format!(
+                " AND m.{} = {}",
+                REGISTER_M_COL,
+                params.push(register)
+            ));
+        }
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM {m_view} AS m
+            WHERE COALESCE(m.{id_dg}, m.{id_g}) = {g_table}.{id_g}{})",
+            meaning_conditions.concat(),
+            g_table = G_TABLE,
+            m_view = M_TABLE_WITH_G_ID,
+            id_dg = ID_DG_COL,
+            id_g = ID_G_COL
+        ));
+    }
+
+    Ok(conditions)
+}
+
+/// ORDER BY expressions of the sort, user values like the seed or the lang are params
+/// `searching` tells if the `matching` search query is joined to rank by relevance
+pub fn order_by(sort: Sort, searching: bool, params: &mut Params) -> String {
+    let order = match sort {
+        Sort::Relevance if searching => "matching.rank DESC".to_owned(),
+        Sort::Relevance | Sort::Newest => format!("{}.{} DESC", G_TABLE, CREATION_COL),
+        Sort::Oldest => format!("{}.{} ASC", G_TABLE, CREATION_COL),
+        Sort::LastUpdated => format!(
+            "COALESCE((SELECT MAX({h_table}.{creation}) FROM {h_table} WHERE {h_table}.{id_g} = {g_table}.{id_g}), {g_table}.{creation}) DESC",
+            g_table = G_TABLE,
+            h_table = H_TABLE,
+            creation = CREATION_COL,
+            id_g = ID_G_COL
+        ),
+        // arrays start at 1, gestures without tag come last
+        Sort::Tag => format!("search_text({}.{}[1]) ASC NULLS LAST", G_TABLE, TAGS_COL),
+        Sort::Meaning { lang } => format!(
+            "(SELECT MIN(search_text(m.{val})) FROM {m_view} AS m
+                WHERE COALESCE(m.{id_dg}, m.{id_g}) = {g_table}.{id_g}{in_lang}) ASC NULLS LAST",
+            in_lang = lang
+                .map(|lang| format!(" AND {} = ANY(m.{})", params.push(lang), LANG_M_COL))
+                .unwrap_or_default(),
+            g_table = G_TABLE,
+            m_view = M_TABLE_WITH_G_ID,
+            val = VALUE_M_COL,
+            id_dg = ID_DG_COL,
+            id_g = ID_G_COL
+        ),
+        Sort::Random { seed: Some(seed) } => format!(
+            "md5({}.{}::text || {}::text)",
+            G_TABLE,
+            ID_G_COL,
+            params.push(seed)
+        ),
+        Sort::Random { seed: None } => "random()".to_owned(),
+    };
+
+    format!("{}, {}.{} DESC", order, G_TABLE, ID_G_COL)
+}
+
+/// ANDed conditions, nothing when there is no condition
+pub fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_should_number_placeholders() {
+        let mut params = Params::default();
+        assert_eq!("$1", params.push("a".to_owned()));
+        assert_eq!("$2", params.push(2));
+        assert_eq!(2, params.refs().len());
+    }
+
+    #[test]
+    fn filter_conditions_should_be_empty_without_filter() {
+        let mut params = Params::default();
+        let conditions = filter_conditions(FilterRequest::default(), &mut params).unwrap();
+
+        assert!(conditions.is_empty());
+        assert_eq!("", where_clause(&conditions));
+    }
+
+    #[test]
+    fn filter_conditions_should_follow_previous_params() {
+        let mut params = Params::default();
+        params.push("search".to_owned());
+        let conditions = filter_conditions(
+            FilterRequest {
+                tags: vec!["insult".to_owned()],
+                has_pictures: Some(true),
+                ..FilterRequest::default()
+            },
+            &mut params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "gestures.tags @> $2::text[]".to_owned(),
+                "EXISTS (SELECT 1 FROM pictures WHERE pictures.id_gesture = gestures.id_gesture) = $3"
+                    .to_owned()
+            ],
+            conditions
+        );
+        assert_eq!(3, params.refs().len());
+    }
+
+    #[test]
+    fn filter_conditions_should_match_region_and_register_on_one_meaning() {
+        let mut params = Params::default();
+        let conditions = filter_conditions(
+            FilterRequest {
+                region: Some("it".to_owned()),
+                register: Some("offensive".to_owned()),
+                ..FilterRequest::default()
+            },
+            &mut params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!["EXISTS (SELECT 1 FROM meanings_with_gesture_id AS m
+            WHERE COALESCE(m.id_description_gesture, m.id_gesture) = gestures.id_gesture AND (m.regions = '{}' OR $1::text = ANY(m.regions)) AND m.register = $2)"
+                .to_owned()],
+            conditions
+        );
+        assert_eq!(2, params.refs().len());
+    }
+
+    #[test]
+    fn order_by_should_rank_only_when_searching() {
+        let mut params = Params::default();
+
+        assert_eq!(
+            "matching.rank DESC, gestures.id_gesture DESC",
+            order_by(Sort::Relevance, true, &mut params)
+        );
+        assert_eq!(
+            "gestures.creation_date DESC, gestures.id_gesture DESC",
+            order_by(Sort::Relevance, false, &mut params)
+        );
+        assert!(params.refs().is_empty());
+    }
+
+    #[test]
+    fn order_by_should_pass_the_seed_as_param() {
+        let mut params = Params::default();
+        params.push("search".to_owned());
+
+        assert_eq!(
+            "md5(gestures.id_gesture::text || $2::text), gestures.id_gesture DESC",
+            order_by(
+                Sort::Random {
+                    seed: Some("'; DROP TABLE gestures; --".to_owned())
+                },
+                false,
+                &mut params
+            )
+        );
+        assert_eq!(2, params.refs().len());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::models::raw::*;
+use crate::models::SearchRequest;
+use crate::query::Params;
+
+/// Below this number of full text hits, gestures with close texts are searched too
+const FUZZY_BELOW_HITS: i64 = 5;
+/// Trigram word similarity a text needs to be close to the input,
+/// set on every connection as the threshold of the `<%` operator
+pub(crate) const FUZZY_MIN_SIMILARITY: f32 = 0.5;
+/// ts_headline options, matched words of the escaped text are wrapped in <mark>
+const HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MinWords=5, MaxWords=20";
+
+/// Query of the gestures matching a search, with their `rank`, the kinds of text they `matched_in`
+/// and a `snippet` of their best description or meaning, None when the input has no word
+/// The snippet is HTML: the text is escaped and matched words are in <mark>
+///
+/// Every language variant of a text is ranked with its own dictionary,
+/// the best ranked text of a gesture gives the gesture rank.
+/// Texts are first found with the query in every dictionary, which the GIN indexes can answer,
+/// then checked with the query of their own dictionary.
+/// When few gestures match, texts close to the input are added below them with a negative rank.
+/// A gesture with an excluded term in any of its texts is left out of both
+pub fn matching_query(search: SearchRequest, params: &mut Params) -> Option<String> {
+    let text = params.push(to_tsquery(&search.text)?);
+    let fuzzy = params.push(fuzzy_text(&search.text));
+    let excluded = params.push(excluded_tsquery(&search.text));
+    let lang = params.push(search.lang);
+
+    Some(format!(
+        "WITH excluded AS (
+            SELECT {id_g} FROM {s_table}
+            WHERE {document} @@ any_lang_tsquery({excluded})
+            AND {document} @@ to_tsquery(lang_config({lang_s}), search_text({excluded}))
+        ), full_text AS (
+            SELECT {id_g}, MAX(ts_rank({document}, to_tsquery(lang_config({lang_s}), search_text({text})))) AS rank,
+                array_agg(DISTINCT {kind}) AS matched_in,
+                (array_agg(
+                    ts_headline(lang_config({lang_s}), html_escape({val}), to_tsquery(lang_config({lang_s}), search_text({text})), '{headline}')
+                    ORDER BY ts_rank({document}, to_tsquery(lang_config({lang_s}), search_text({text}))) DESC
+                ) FILTER (WHERE {kind} <> 'tag'))[1] AS snippet
+            FROM {s_table}
+            WHERE ({lang}::text IS NULL OR {lang_s} IS NULL OR {lang_s} = {lang})
+            AND {document} @@ any_lang_tsquery({text})
+            AND {document} @@ to_tsquery(lang_config({lang_s}), search_text({text}))
+            AND {id_g} NOT IN (SELECT {id_g} FROM excluded)
+            GROUP BY {id_g}
+        ), fuzzy AS (
+            SELECT {id_g}, MAX(word_similarity(search_text({fuzzy}), {normalized})) - 1 AS rank,
+                array_agg(DISTINCT {kind}) AS matched_in, NULL AS snippet
+            FROM {fs_table}
+            WHERE ({lang}::text IS NULL OR {lang_s} IS NULL OR {lang_s} = {lang})
+            AND search_text({fuzzy}) <% {normalized}
+            AND {id_g} NOT IN (SELECT {id_g} FROM full_text)
+            AND {id_g} NOT IN (SELECT {id_g} FROM excluded)
+            AND (SELECT COUNT(*) FROM full_text) < {few}
+            GROUP BY {id_g}
+        )
+        SELECT * FROM full_text UNION ALL SELECT * FROM fuzzy",
+        text = text,
+        fuzzy = fuzzy,
+        excluded = excluded,
+        lang = lang,
+        id_g = ID_G_COL,
+        s_table = SEARCHABLE_VIEW,
+        fs_table = FUZZY_SEARCHABLE_VIEW,
+        document = DOCUMENT,
+        lang_s = LANG_S_COL,
+        normalized = NORMALIZED_S_COL,
+        val = VALUE_D_COL,
+        kind = KIND_S_COL,
+        headline = HEADLINE_OPTIONS,
+        few = FUZZY_BELOW_HITS
+    ))
+}
+
+/// Build a tsquery from text typed by a user, None when there is nothing to search
+///
+/// - words are ANDed
+/// - "quoted words" have to follow each other
+/// - the last word is a prefix so results show up while typing
+/// - -word excludes texts containing it
+///
+/// Only alphanumeric chars reach the tsquery, so any input gives a valid query
+fn to_tsquery(input: &str) -> Option<String> {
+    let terms = terms(input);
+    let last = terms.len().checked_sub(1)?;
+
+    let query = terms
+        .iter()
+        .enumerate()
+        .map(|(i, term)| term.to_tsquery(i == last && !term.quoted && !term.excluded))
+        .collect::<Vec<String>>()
+        .join(" & ");
+
+    Some(query)
+}
+
+/// Words a text should be close to when it does not match the tsquery, excluded terms are left out
+fn fuzzy_text(input: &str) -> Option<String> {
+    let words = terms(input)
+        .into_iter()
+        .filter(|term| !term.excluded)
+        .flat_map(|term| term.words)
+        .collect::<Vec<String>>();
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+/// Tsquery matching texts with any excluded term, so the gestures of these texts are skipped
+fn excluded_tsquery(input: &str) -> Option<String> {
+    let excluded = terms(input)
+        .into_iter()
+        .filter(|term| term.excluded)
+        .map(|term| {
+            Term {
+                excluded: false,
+                ..term
+            }
+            .to_tsquery(false)
+        })
+        .collect::<Vec<String>>();
+
+    if excluded.is_empty() {
+        None
+    } else {
+        Some(excluded.join(" | "))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+struct Term {
+    words: Vec<String>,
+    quoted: bool,
+    excluded: bool,
+}
+
+impl Term {
+    fn to_tsquery(&self, prefix: bool) -> String {
+        let mut query = self.words.join(" <-> ");
+        if prefix {
+            query.push_str(":*");
+        }
+        if self.words.len() > 1 {
+            query = format!("({})", query);
+        }
+        if self.excluded {
+            query = format!("!{}", query);
+        }
+        query
+    }
+}
+
+fn terms(input: &str) -> Vec<Term> {
+    let mut terms = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let excluded = c == '-';
+        let first = if excluded {
+            match chars.peek() {
+                Some(&c) if !c.is_whitespace() => {
+                    chars.next();
+                    c
+                }
+                // a lonely dash excludes nothing
+                _ => continue,
+            }
+        } else {
+            c
+        };
+
+        let quoted = first == '"';
+        let mut raw = String::new();
+        if !quoted {
+            raw.push(first);
+        }
+        while let Some(&c) = chars.peek() {
+            chars.next();
+            if (quoted && c == '"') || (!quoted && c.is_whitespace()) {
+                break;
+            }
+            raw.push(c);
+        }
+
+        let words = words(&raw);
+        if !words.is_empty() {
+            terms.push(Term {
+                words,
+                quoted,
+                excluded,
+            });
+        }
+    }
+
+    terms
+}
+
+/// Lowercase alphanumeric words, everything else separates them
+fn words(raw: &str) -> Vec<String> {
+    raw.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_prefix_single_word() {
+        assert_eq!(Some("bras:*".to_owned()), to_tsquery("bras"));
+    }
+
+    #[test]
+    fn should_and_words_and_prefix_the_last() {
+        assert_eq!(
+            Some("petite & description:*".to_owned()),
+            to_tsquery("  Petite   description ")
+        );
+    }
+
+    #[test]
+    fn should_keep_quoted_words_together() {
+        assert_eq!(
+            Some("(minute <-> papillon) & vite:*".to_owned()),
+            to_tsquery("\"minute papillon\" vite")
+        );
+    }
+
+    #[test]
+    fn should_not_prefix_a_quoted_last_term() {
+        assert_eq!(
+            Some("(minute <-> papillon)".to_owned()),
+            to_tsquery("\"minute papillon\"")
+        );
+    }
+
+    #[test]
+    fn should_exclude_dashed_terms() {
+        assert_eq!(
+            Some("!jambe & !(tard <-> jamais) & bras:*".to_owned()),
+            to_tsquery("-jambe -\"tard, jamais\" bras")
+        );
+    }
+
+    #[test]
+    fn should_ignore_lonely_dash() {
+        assert_eq!(Some("a & b:*".to_owned()), to_tsquery("a - b"));
+    }
+
+    #[test]
+    fn should_drop_tsquery_operators() {
+        assert_eq!(Some("a & b & c:*".to_owned()), to_tsquery("a & b | !(c)"));
+        assert_eq!(Some("d:*".to_owned()), to_tsquery("'d':*"));
+    }
+
+    #[test]
+    fn should_split_words_inside_a_term() {
+        assert_eq!(Some("(l <-> eau:*)".to_owned()), to_tsquery("l'eau"));
+    }
+
+    #[test]
+    fn should_keep_accents() {
+        assert_eq!(Some("gèste:*".to_owned()), to_tsquery("Gèste"));
+    }
+
+    #[test]
+    fn should_return_none_without_words() {
+        assert_eq!(None, to_tsquery(""));
+        assert_eq!(None, to_tsquery(" & | ! \"\" - ()"));
+    }
+
+    #[test]
+    fn should_accept_unclosed_quote() {
+        assert_eq!(Some("(a <-> b)".to_owned()), to_tsquery("\"a b"));
+    }
+
+    #[test]
+    fn fuzzy_text_should_keep_included_words() {
+        assert_eq!(
+            Some("minute papillon moqeur".to_owned()),
+            fuzzy_text("\"Minute papillon\" -jambe moqeur")
+        );
+    }
+
+    #[test]
+    fn excluded_tsquery_should_or_excluded_terms() {
+        assert_eq!(
+            Some("jambe | (tard <-> jamais)".to_owned()),
+            excluded_tsquery("bras -jambe -\"tard jamais\"")
+        );
+        assert_eq!(None, excluded_tsquery("bras"));
+    }
+
+    #[test]
+    fn fuzzy_text_should_return_none_with_only_exclusions() {
+        assert_eq!(None, fuzzy_text("-jambe"));
+    }
+}
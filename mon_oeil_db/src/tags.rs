@@ -0,0 +1,44 @@
+/// Form tags are stored and searched in: trimmed, lowercase, words separated by a single space
+/// so "Insulte" and " insulte " are the same tag
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Normalized tags without empty ones and duplicates, in the order they were first given
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = normalize_tag(&tag);
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tag_should_lowercase_and_collapse_spaces() {
+        assert_eq!("doigt d'honneur", normalize_tag("  Doigt   D'honneur "));
+        assert_eq!("écœuré", normalize_tag("ÉCŒURÉ"));
+    }
+
+    #[test]
+    fn normalize_tags_should_drop_empty_and_duplicates() {
+        assert_eq!(
+            vec!["insulte".to_owned(), "main".to_owned()],
+            normalize_tags(vec![
+                "Insulte".to_owned(),
+                " ".to_owned(),
+                "main".to_owned(),
+                "insulte ".to_owned(),
+            ])
+        );
+    }
+}
@@ -24,43 +24,51 @@ function get_gestures(page, search_text) {
       });
 }
 
-function delete_gesture(id) {
-  return client.delete('gestures/' + id).then(() => undefined)
+function get_languages() {
+  return client.get('languages').then(res => res.data)
 }
 
-function delete_description(id) {
-  return client.delete('descriptions/' + id).then(() => undefined)
+function if_match(version) {
+  return { 'If-Match': '"' + version + '"' };
 }
 
-function delete_meaning(id) {
-  return client.delete('meanings/' + id).then(() => undefined)
+function delete_gesture(id, version) {
+  return client.delete('gestures/' + id, { headers: if_match(version) }).then(() => undefined)
 }
 
-function delete_picture(id) {
-  return client.delete('pictures/' + id).then(() => undefined)
+function delete_description(id, version) {
+  return client.delete('descriptions/' + id, { headers: if_match(version) }).then(() => undefined)
 }
 
-function put_description(id, new_description) {
-  return client.put('descriptions/' + id,  new_description).then(() => undefined)
+function delete_meaning(id, version) {
+  return client.delete('meanings/' + id, { headers: if_match(version) }).then(() => undefined)
 }
 
-function put_meaning(id, new_meaning) {
-  return client.put('meanings/' + id,  new_meaning).then(() => undefined)
+function delete_picture(id, version) {
+  return client.delete('pictures/' + id, { headers: if_match(version) }).then(() => undefined)
 }
 
-function put_gesture(id, new_gesture) {
-  return client.put('gestures/' + id,  new_gesture).then(() => undefined)
+function put_description(id, version, new_description) {
+  return client.put('descriptions/' + id,  new_description, { headers: if_match(version) }).then(() => undefined)
 }
 
-function put_picture_meta(id, new_picture_meta) {
-  return client.put('pictures/' + id + '/meta',  new_picture_meta).then(() => undefined)
+function put_meaning(id, version, new_meaning) {
+  return client.put('meanings/' + id,  new_meaning, { headers: if_match(version) }).then(() => undefined)
 }
 
-function put_picture_file(id, new_picture_file) {
+function put_gesture(id, version, new_gesture) {
+  return client.put('gestures/' + id,  new_gesture, { headers: if_match(version) }).then(() => undefined)
+}
+
+function put_picture_meta(id, version, new_picture_meta) {
+  return client.put('pictures/' + id + '/meta',  new_picture_meta, { headers: if_match(version) }).then(() => undefined)
+}
+
+function put_picture_file(id, version, new_picture_file) {
   let formData = new FormData();
   formData.append('picture', new_picture_file);
 
-  return client.put('pictures/' + id + '/file',  formData, { headers: { 'Content-Type': 'multipart/form-data' } }).then(() => undefined)
+  return client.put('pictures/' + id + '/file',  formData, { headers: { 'Content-Type': 'multipart/form-data', ...if_match(version) } }).then(() => undefined)
 }
 
 function post_description_meaning(id_description, new_meaning) {
@@ -111,6 +119,7 @@ client.interceptors.request.use(
 
 export const service = {
   get_gestures,
+  get_languages,
   delete_gesture,
   delete_description,
   delete_meaning,
@@ -103,9 +103,9 @@ export default new Vuex.Store({
       })
       context.commit("stop_loading");
     },
-    del_gesture(context, id) {
+    del_gesture(context, { id, version }) {
       context.commit("start_loading");
-      service.delete_gesture(id).then(() => {
+      service.delete_gesture(id, version).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -114,9 +114,9 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    del_description(context, id) {
+    del_description(context, { id, version }) {
       context.commit("start_loading");
-      service.delete_description(id).then(() => {
+      service.delete_description(id, version).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -125,9 +125,9 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    del_meaning(context, id) {
+    del_meaning(context, { id, version }) {
       context.commit("start_loading");
-      service.delete_meaning(id).then(() => {
+      service.delete_meaning(id, version).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -136,9 +136,9 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    del_picture(context, id) {
+    del_picture(context, { id, version }) {
       context.commit("start_loading");
-      service.delete_picture(id).then(() => {
+      service.delete_picture(id, version).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -147,9 +147,9 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    update_description(context, { id, new_description }) {
+    update_description(context, { id, version, new_description }) {
       context.commit("start_loading"); 
-      service.put_description(id, new_description).then(() => {
+      service.put_description(id, version, new_description).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -158,8 +158,8 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    update_meaning(context, { id, new_meaning }) {
-      service.put_meaning(id, new_meaning).then(() => {
+    update_meaning(context, { id, version, new_meaning }) {
+      service.put_meaning(id, version, new_meaning).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -168,8 +168,8 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    update_gesture(context, { id, new_gesture }) {
-      service.put_gesture(id, new_gesture).then(() => {
+    update_gesture(context, { id, version, new_gesture }) {
+      service.put_gesture(id, version, new_gesture).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -178,8 +178,8 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    update_picture_meta(context, { id, new_picture_meta }) {
-      service.put_picture_meta(id, new_picture_meta).then(() => {
+    update_picture_meta(context, { id, version, new_picture_meta }) {
+      service.put_picture_meta(id, version, new_picture_meta).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
@@ -188,8 +188,8 @@ export default new Vuex.Store({
         context.commit("stop_loading");
       });
     },
-    update_picture_file(context, { id, new_picture_file }) {
-      service.put_picture_file(id, new_picture_file).then(() => {
+    update_picture_file(context, { id, version, new_picture_file }) {
+      service.put_picture_file(id, version, new_picture_file).then(() => {
         context.dispatch('load_gestures');
         context.commit('notif', notif_ok);
       }).catch((e) => {
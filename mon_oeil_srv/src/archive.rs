@@ -0,0 +1,244 @@
+use bytes::Bytes;
+use chrono::NaiveDateTime;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use mon_oeil_core::*;
+use mon_oeil_storage::Storage;
+
+/// Languages dump of an archive, in the shape of the api
+pub const LANGUAGES_FILE: &str = "languages.json";
+/// Category tree dump of an archive, in the shape of the api
+pub const CATEGORIES_FILE: &str = "categories.json";
+/// Gestures dump of an archive, in the shape of the api
+pub const GESTURES_FILE: &str = "gestures.json";
+/// Creation dates dump of an archive, by id of gesture, description, meaning or picture
+pub const CREATION_DATES_FILE: &str = "creation_dates.json";
+
+/// Tar archive of the languages, categories, gestures and creation dates dumps then of every
+/// picture file
+/// Each picture is downloaded from storage when its turn comes
+pub fn archive_stream(
+    export: GesturesExport,
+    storage: Arc<Storage>,
+) -> impl Stream<Item = Result<Bytes, Error>> {
+    let GesturesExport {
+        languages,
+        categories,
+        gestures,
+        creation_dates,
+        pictures,
+    } = export;
+    let dump = dump_entry(LANGUAGES_FILE, &languages).and_then(|mut dump| {
+        dump.extend_from_slice(&dump_entry(CATEGORIES_FILE, &categories)?);
+        dump.extend_from_slice(&dump_entry(GESTURES_FILE, &gestures)?);
+        dump.extend_from_slice(&dump_entry(CREATION_DATES_FILE, &creation_dates)?);
+        Ok(Bytes::from(dump))
+    });
+
+    let files = stream::iter(pictures).then(move |picture| {
+        let storage = storage.clone();
+        async move {
+            let content = storage.download(&picture.id, &picture.format).await?;
+            tar_entry(&picture.path(), &content)
+        }
+    });
+
+    // two empty blocks end a tar archive
+    let end = Ok(Bytes::from(vec![0; 1024]));
+
+    stream::once(async move { dump })
+        .chain(files)
+        .chain(stream::once(async move { end }))
+}
+
+/// Tar entry of a json dump
+fn dump_entry(path: &str, value: &impl serde::Serialize) -> Result<Vec<u8>, Error> {
+    let dump = serde_json::to_vec_pretty(value).map_err(|e| Error::Bug(e.to_string()))?;
+    Ok(tar_entry(path, &dump)?.to_vec())
+}
+
+/// Header and content of a file padded to the 512 bytes blocks of tar
+fn tar_entry(path: &str, content: &[u8]) -> Result<Bytes, Error> {
+    let mut header = tar::Header::new_gnu();
+    header
+        .set_path(path)
+        .map_err(|e| Error::Bug(e.to_string()))?;
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let padding = (512 - content.len() % 512) % 512;
+    let mut entry = Vec::with_capacity(512 + content.len() + padding);
+    entry.extend_from_slice(header.as_bytes());
+    entry.extend_from_slice(content);
+    entry.resize(entry.len() + padding, 0);
+
+    Ok(Bytes::from(entry))
+}
+
+/// `export <file.tar>` subcommand, return the exit code
+pub async fn run_export(args: &[String]) -> i32 {
+    let path = match args {
+        [path] => path,
+        _ => {
+            eprintln!("Usage: mon_oeil_srv export <file.tar>");
+            return 1;
+        }
+    };
+    let db_pool = match mon_oeil_db::connect_db() {
+        Ok(db_pool) => db_pool,
+        Err(e) => {
+            eprintln!("Database configuration failed: {}", e);
+            return 1;
+        }
+    };
+    let storage = Arc::new(crate::build_storage());
+
+    let export = match handlers::export_all_gestures(&db_pool, &storage).await {
+        Ok(export) => export,
+        Err(e) => {
+            eprintln!("Export failed: {:?}", e);
+            return 1;
+        }
+    };
+    let (gestures, pictures) = (export.gestures.len(), export.pictures.len());
+
+    let mut file = match File::create(path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Can't create {}: {}", path, e);
+            return 1;
+        }
+    };
+    let mut archive = Box::pin(archive_stream(export, storage));
+    while let Some(chunk) = archive.next().await {
+        let written = chunk
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|chunk| file.write_all(&chunk).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("Export failed, {} is incomplete: {}", path, e);
+            return 1;
+        }
+    }
+
+    println!(
+        "Exported {} gestures and {} pictures to {}",
+        gestures, pictures, path
+    );
+    0
+}
+
+/// `restore <file.tar> [--author <name>]` subcommand, return the exit code
+pub async fn run_restore(args: &[String]) -> i32 {
+    let (path, author) = match args {
+        [path] => (path, None),
+        [path, flag, author] if flag == "--author" => (path, Some(author.clone())),
+        _ => {
+            eprintln!("Usage: mon_oeil_srv restore <file.tar> [--author <name>]");
+            return 1;
+        }
+    };
+
+    let archive = match read_archive(path) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Can't read {}: {}", path, e);
+            return 1;
+        }
+    };
+    let count = archive.gestures.len();
+
+    let db_pool = match mon_oeil_db::connect_db() {
+        Ok(db_pool) => db_pool,
+        Err(e) => {
+            eprintln!("Database configuration failed: {}", e);
+            return 1;
+        }
+    };
+    let storage = crate::build_storage();
+
+    let Archive {
+        languages,
+        categories,
+        gestures,
+        creation_dates,
+        files,
+    } = archive;
+    match handlers::restore_gestures(
+        &db_pool,
+        &storage,
+        languages,
+        categories,
+        gestures,
+        creation_dates,
+        files,
+        author,
+    )
+    .await
+    {
+        Ok(()) => {
+            println!("Restored {} gestures from {}", count, path);
+            0
+        }
+        Err(e) => {
+            eprintln!("Restore failed, nothing was restored: {:?}", e);
+            1
+        }
+    }
+}
+
+/// Dumps and picture files of an archive
+struct Archive {
+    languages: Vec<Language>,
+    categories: Vec<Category>,
+    gestures: Vec<Gesture>,
+    creation_dates: BTreeMap<String, NaiveDateTime>,
+    files: Vec<(PictureFile, Vec<u8>)>,
+}
+
+/// Dumps and picture files of an archive, archives made before the languages, categories and
+/// creation dates dumps restore none, their rows are created now
+fn read_archive(path: &str) -> Result<Archive, String> {
+    let mut archive = tar::Archive::new(File::open(path).map_err(|e| e.to_string())?);
+    let mut languages = vec![];
+    let mut categories = vec![];
+    let mut gestures = None;
+    let mut creation_dates = BTreeMap::new();
+    let mut files = Vec::new();
+
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let entry_path = entry
+            .path()
+            .map_err(|e| e.to_string())?
+            .to_string_lossy()
+            .into_owned();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(|e| e.to_string())?;
+
+        if entry_path == LANGUAGES_FILE {
+            languages = serde_json::from_slice(&content).map_err(|e| e.to_string())?;
+        } else if entry_path == CATEGORIES_FILE {
+            categories = serde_json::from_slice(&content).map_err(|e| e.to_string())?;
+        } else if entry_path == GESTURES_FILE {
+            gestures = Some(serde_json::from_slice(&content).map_err(|e| e.to_string())?);
+        } else if entry_path == CREATION_DATES_FILE {
+            creation_dates = serde_json::from_slice(&content).map_err(|e| e.to_string())?;
+        } else if let Some(file) = PictureFile::from_path(&entry_path) {
+            files.push((file, content));
+        }
+    }
+
+    let gestures = gestures.ok_or_else(|| format!("{} is missing", GESTURES_FILE))?;
+    Ok(Archive {
+        languages,
+        categories,
+        gestures,
+        creation_dates,
+        files,
+    })
+}
@@ -1,28 +1,66 @@
 use actix_multipart::Multipart;
-use actix_web::{error, web, Error, HttpRequest, HttpResponse, Responder};
+use actix_web::{
+    http::{header, StatusCode},
+    web, Error, HttpRequest, HttpResponse, Responder,
+};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use bytes::buf::BufMut;
 use bytes::BytesMut;
 use futures::{StreamExt, TryStreamExt};
 use log::error;
 use serde::Deserialize;
+use std::collections::HashMap;
 
-use crate::{ApiError, Conf};
+use crate::{archive::archive_stream, json_error, ApiError, Conf};
 use mon_oeil_core::*;
 use mon_oeil_db as db;
 
 pub fn app_config(config: &mut web::ServiceConfig) {
     config
         .route("/gestures", web::get().to(get_gestures))
+        .route("/gestures/random", web::get().to(get_random_gesture))
+        .route("/gestures/daily", web::get().to(get_daily_gesture))
+        .route("/suggest", web::get().to(get_suggestions))
+        .route("/tags", web::get().to(get_tags))
+        .route("/tags/merge", web::post().to(merge_tags))
+        .route("/tags/{tag}", web::put().to(put_tag))
         .route("/gestures", web::post().to(post_gesture))
         .route("/gestures/{id}", web::put().to(put_gesture))
         .route("/gestures/{id}", web::delete().to(delete_gesture))
+        .route(
+            "/gestures/{id}/categories",
+            web::put().to(put_gesture_categories),
+        )
+        .route(
+            "/gestures/{id}/related/{id_related}",
+            web::put().to(put_related_gesture),
+        )
+        .route(
+            "/gestures/{id}/related/{id_related}",
+            web::delete().to(delete_related_gesture),
+        )
         .route(
             "/gestures/{id_gesutre}/descriptions",
             web::post().to(post_description),
         )
         .route("/descriptions/{id}", web::put().to(put_description))
         .route("/descriptions/{id}", web::delete().to(delete_description))
+        .route(
+            "/descriptions/{id}/translation",
+            web::put().to(put_description_translation),
+        )
+        .route(
+            "/descriptions/{id}/translation",
+            web::delete().to(delete_description_translation),
+        )
+        .route(
+            "/descriptions/{id}/source",
+            web::put().to(put_description_source),
+        )
+        .route(
+            "/descriptions/{id}/source",
+            web::delete().to(delete_description_source),
+        )
         .route(
             "/gestures/{id_gesutre}/meanings",
             web::post().to(post_gesture_s_meaning),
@@ -33,13 +71,50 @@ pub fn app_config(config: &mut web::ServiceConfig) {
         )
         .route("/meanings/{id}", web::put().to(put_meaning))
         .route("/meanings/{id}", web::delete().to(delete_meaning))
+        .route(
+            "/meanings/{id}/translation",
+            web::put().to(put_meaning_translation),
+        )
+        .route(
+            "/meanings/{id}/translation",
+            web::delete().to(delete_meaning_translation),
+        )
+        .route("/meanings/{id}/source", web::put().to(put_meaning_source))
+        .route(
+            "/meanings/{id}/source",
+            web::delete().to(delete_meaning_source),
+        )
+        .route(
+            "/translations/missing",
+            web::get().to(get_missing_translations),
+        )
         .route(
             "/gestures/{id_gesutre}/pictures",
             web::post().to(post_picture),
         )
         .route("/pictures/{id}/meta", web::put().to(put_picture_meta))
         .route("/pictures/{id}/file", web::put().to(put_picture_file))
-        .route("/pictures/{id}", web::delete().to(delete_picture));
+        .route("/pictures/{id}", web::delete().to(delete_picture))
+        .route("/pictures/{id}/source", web::put().to(put_picture_source))
+        .route(
+            "/pictures/{id}/source",
+            web::delete().to(delete_picture_source),
+        )
+        .route(
+            "/gestures/{id_gesture}/history",
+            web::get().to(get_gesture_history),
+        )
+        .route("/history/{id}/revert", web::post().to(revert_revision))
+        .route("/categories", web::get().to(get_categories))
+        .route("/categories", web::post().to(post_category))
+        .route("/categories/{id}", web::put().to(put_category))
+        .route("/categories/{id}", web::delete().to(delete_category))
+        .route("/languages", web::get().to(get_languages))
+        .route("/languages", web::post().to(post_language))
+        .route("/languages/{code}", web::put().to(put_language))
+        .route("/languages/{code}", web::delete().to(delete_language))
+        .route("/import", web::post().to(post_import))
+        .route("/export", web::get().to(get_export));
 }
 
 impl Into<Error> for ApiError<mon_oeil_core::Error> {
@@ -47,11 +122,46 @@ impl Into<Error> for ApiError<mon_oeil_core::Error> {
         match self.0 {
             mon_oeil_core::Error::Bug(e) => {
                 error!("{:?}", e);
-                error::ErrorInternalServerError("")
+                json_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal",
+                    "Unexpected error".to_owned(),
+                )
+            }
+            mon_oeil_core::Error::Auth => json_error(
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "Invalid or missing token".to_owned(),
+            ),
+            mon_oeil_core::Error::NotFound => json_error(
+                StatusCode::NOT_FOUND,
+                "not_found",
+                "Resource not found".to_owned(),
+            ),
+            mon_oeil_core::Error::RelatedNotFound(entity) => json_error(
+                StatusCode::NOT_FOUND,
+                "not_found",
+                format!("The {} does not exist", entity),
+            ),
+            mon_oeil_core::Error::NotAccepted(x) => {
+                json_error(StatusCode::BAD_REQUEST, "bad_request", x)
             }
-            mon_oeil_core::Error::Auth => error::ErrorUnauthorized(""),
-            mon_oeil_core::Error::NotFound => error::ErrorNotFound(""),
-            mon_oeil_core::Error::NotAccepted(x) => error::ErrorBadRequest(x),
+            mon_oeil_core::Error::Conflict(x) => json_error(StatusCode::CONFLICT, "conflict", x),
+            mon_oeil_core::Error::PreconditionRequired => json_error(
+                StatusCode::PRECONDITION_REQUIRED,
+                "precondition_required",
+                "If-Match header is required".to_owned(),
+            ),
+            mon_oeil_core::Error::PreconditionFailed => json_error(
+                StatusCode::PRECONDITION_FAILED,
+                "precondition_failed",
+                "The resource has been modified".to_owned(),
+            ),
+            mon_oeil_core::Error::Unavailable => json_error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "unavailable",
+                "Service temporarily unavailable".to_owned(),
+            ),
         }
     }
 }
@@ -62,27 +172,139 @@ impl From<mon_oeil_core::Error> for ApiError<mon_oeil_core::Error> {
     }
 }
 
+fn if_match(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn updated(version: i32) -> HttpResponse {
+    HttpResponse::Created()
+        .header("Access-Control-Expose-Headers", "etag")
+        .header(header::ETAG, format!("\"{}\"", version))
+        .finish()
+}
+
 async fn get_gestures(
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     storage: web::Data<mon_oeil_storage::Storage>,
+    conf: web::Data<Conf>,
     search_param: web::Query<mon_oeil_core::SearchParam>,
 ) -> Result<impl Responder, ApiError<mon_oeil_core::Error>> {
     let max = search_param.max;
-    handlers::get_gestures(&db, &storage, search_param.into_inner())
+    // new clients get an envelope, pages of a bare array stay for the existing front
+    let envelope = search_param.cursor.is_some() || search_param.facets;
+    let accept_language = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    handlers::get_gestures(
+        &db,
+        &storage,
+        search_param.into_inner(),
+        accept_language,
+        &conf.fallback_langs,
+    )
+    .await
+    .map(|page| {
+        let mut res = if envelope || i64::from(max) >= page.total {
+            HttpResponse::Ok()
+        } else {
+            let mut res = HttpResponse::PartialContent();
+            res.header("Access-Control-Expose-Headers", "total-items")
+                .header("total-items", format!("{}", page.total));
+            res
+        };
+        res.header(header::VARY, "Accept-Language");
+        if !page.content_langs.is_empty() {
+            res.header(header::CONTENT_LANGUAGE, page.content_langs.join(", "));
+        }
+        if envelope {
+            res.json(page)
+        } else {
+            res.json(page.items)
+        }
+    })
+    .map_err(ApiError::from)
+}
+
+async fn get_random_gesture(
+    db: web::Data<db::GestureClientPool>,
+    storage: web::Data<mon_oeil_storage::Storage>,
+    random_param: web::Query<RandomParam>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_random_gesture(&db, &storage, random_param.into_inner())
         .await
-        .map(|(gestures, total)| {
-            if max >= total {
-                HttpResponse::Ok().json(gestures)
-            } else {
-                HttpResponse::PartialContent()
-                    .header("Access-Control-Expose-Headers", "total-items")
-                    .header("total-items", format!("{}", total))
-                    .json(gestures)
-            }
-        })
+        .map(|gesture| HttpResponse::Ok().json(gesture))
         .map_err(ApiError::from)
 }
 
+async fn get_daily_gesture(
+    db: web::Data<db::GestureClientPool>,
+    storage: web::Data<mon_oeil_storage::Storage>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_daily_gesture(&db, &storage)
+        .await
+        .map(|gesture| HttpResponse::Ok().json(gesture))
+        .map_err(ApiError::from)
+}
+
+async fn get_suggestions(
+    db: web::Data<db::GestureClientPool>,
+    param: web::Query<SuggestParam>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_suggestions(&db, param.into_inner())
+        .await
+        .map(|suggestions| HttpResponse::Ok().json(suggestions))
+        .map_err(ApiError::from)
+}
+
+async fn get_tags(
+    db: web::Data<db::GestureClientPool>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_tags(&db)
+        .await
+        .map(|tags| HttpResponse::Ok().json(tags))
+        .map_err(ApiError::from)
+}
+
+async fn put_tag(
+    db: web::Data<db::GestureClientPool>,
+    tag: web::Path<String>,
+    tag_name: web::Json<TagName>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_tag(
+        &db,
+        &tag,
+        tag_name.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn merge_tags(
+    db: web::Data<db::GestureClientPool>,
+    tags_merge: web::Json<TagsMerge>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::merge_tags(
+        &db,
+        tags_merge.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
 async fn post_gesture(
     _req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
@@ -102,7 +324,7 @@ async fn post_gesture(
 }
 
 async fn put_gesture(
-    _req: HttpRequest,
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     new_gesture: web::Json<NewGesture>,
@@ -113,25 +335,32 @@ async fn put_gesture(
         &db,
         &id,
         new_gesture.into_inner(),
+        if_match(&req),
         &conf.hs256_private_key,
         credentials.token(),
     )
     .await
-    .map(|_| HttpResponse::Created().finish())
+    .map(updated)
     .map_err(ApiError::from)
 }
 
 async fn delete_gesture(
-    _req: HttpRequest,
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     conf: web::Data<Conf>,
     credentials: BearerAuth,
 ) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
-    handlers::delete_gesture(&db, &id, &conf.hs256_private_key, credentials.token())
-        .await
-        .map(|_| HttpResponse::Created().finish())
-        .map_err(ApiError::from)
+    handlers::delete_gesture(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
 }
 
 async fn post_description(
@@ -155,6 +384,7 @@ async fn post_description(
 }
 
 async fn put_description(
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     new_description: web::Json<NewDescription>,
@@ -165,25 +395,32 @@ async fn put_description(
         &db,
         &id,
         new_description.into_inner(),
+        if_match(&req),
         &conf.hs256_private_key,
         credentials.token(),
     )
     .await
-    .map(|_| HttpResponse::Created().finish())
+    .map(updated)
     .map_err(ApiError::from)
 }
 
 async fn delete_description(
-    _req: HttpRequest,
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     conf: web::Data<Conf>,
     credentials: BearerAuth,
 ) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
-    handlers::delete_description(&db, &id, &conf.hs256_private_key, credentials.token())
-        .await
-        .map(|_| HttpResponse::Created().finish())
-        .map_err(ApiError::from)
+    handlers::delete_description(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
 }
 
 async fn post_gesture_s_meaning(
@@ -227,6 +464,7 @@ async fn post_description_s_meaning(
 }
 
 async fn put_meaning(
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     new_meaning: web::Json<NewMeaning>,
@@ -237,25 +475,32 @@ async fn put_meaning(
         &db,
         &id,
         new_meaning.into_inner(),
+        if_match(&req),
         &conf.hs256_private_key,
         credentials.token(),
     )
     .await
-    .map(|_| HttpResponse::Created().finish())
+    .map(updated)
     .map_err(ApiError::from)
 }
 
 async fn delete_meaning(
-    _req: HttpRequest,
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     conf: web::Data<Conf>,
     credentials: BearerAuth,
 ) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
-    handlers::delete_meaning(&db, &id, &conf.hs256_private_key, credentials.token())
-        .await
-        .map(|_| HttpResponse::Created().finish())
-        .map_err(ApiError::from)
+    handlers::delete_meaning(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
 }
 
 #[derive(Debug, Deserialize)]
@@ -305,6 +550,7 @@ async fn post_picture(
 }
 
 async fn put_picture_file(
+    req: HttpRequest,
     files: Multipart,
     id: web::Path<String>,
     db: web::Data<db::GestureClientPool>,
@@ -319,11 +565,12 @@ async fn put_picture_file(
             &id,
             NewPictureFileInfo { format },
             content,
+            if_match(&req),
             &conf.hs256_private_key,
             credentials.token(),
         )
         .await
-        .map(|_| HttpResponse::Created().finish())
+        .map(updated)
         .map_err(ApiError::from),
         Err(res) => Ok(res),
     }
@@ -350,6 +597,7 @@ async fn extract_file_and_format(mut files: Multipart) -> Result<(Vec<u8>, Strin
 }
 
 async fn put_picture_meta(
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     id: web::Path<String>,
     new_picture_meta: web::Json<NewPictureMeta>,
@@ -360,16 +608,17 @@ async fn put_picture_meta(
         &db,
         &id,
         new_picture_meta.into_inner(),
+        if_match(&req),
         &conf.hs256_private_key,
         credentials.token(),
     )
     .await
-    .map(|_| HttpResponse::Created().finish())
+    .map(updated)
     .map_err(ApiError::from)
 }
 
 async fn delete_picture(
-    _req: HttpRequest,
+    req: HttpRequest,
     db: web::Data<db::GestureClientPool>,
     storage: web::Data<mon_oeil_storage::Storage>,
     id: web::Path<String>,
@@ -380,6 +629,273 @@ async fn delete_picture(
         &db,
         &storage,
         &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn get_gesture_history(
+    db: web::Data<db::GestureClientPool>,
+    id_gesture: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_gesture_history(
+        &db,
+        &id_gesture,
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|revisions| HttpResponse::Ok().json(revisions))
+    .map_err(ApiError::from)
+}
+
+async fn revert_revision(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<i64>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::revert_revision(&db, *id, &conf.hs256_private_key, credentials.token())
+        .await
+        .map(|_| HttpResponse::Created().finish())
+        .map_err(ApiError::from)
+}
+
+async fn put_description_translation(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    link: web::Json<TranslationLink>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_description_translation(
+        &db,
+        &id,
+        link.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn delete_description_translation(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_description_translation(&db, &id, &conf.hs256_private_key, credentials.token())
+        .await
+        .map(|_| HttpResponse::Created().finish())
+        .map_err(ApiError::from)
+}
+
+async fn put_meaning_translation(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    link: web::Json<TranslationLink>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_meaning_translation(
+        &db,
+        &id,
+        link.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn delete_meaning_translation(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_meaning_translation(&db, &id, &conf.hs256_private_key, credentials.token())
+        .await
+        .map(|_| HttpResponse::Created().finish())
+        .map_err(ApiError::from)
+}
+
+async fn put_description_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    source: web::Json<Source>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_description_source(
+        &db,
+        &id,
+        source.into_inner(),
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn delete_description_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_description_source(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn put_meaning_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    source: web::Json<Source>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_meaning_source(
+        &db,
+        &id,
+        source.into_inner(),
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn delete_meaning_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_meaning_source(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn put_picture_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    source: web::Json<Source>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_picture_source(
+        &db,
+        &id,
+        source.into_inner(),
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn delete_picture_source(
+    req: HttpRequest,
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_picture_source(
+        &db,
+        &id,
+        if_match(&req),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(updated)
+    .map_err(ApiError::from)
+}
+
+async fn get_missing_translations(
+    db: web::Data<db::GestureClientPool>,
+    param: web::Query<MissingTranslationParam>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_missing_translations(&db, param.into_inner())
+        .await
+        .map(|missing| HttpResponse::Ok().json(missing))
+        .map_err(ApiError::from)
+}
+
+async fn get_categories(
+    db: web::Data<db::GestureClientPool>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_categories(&db)
+        .await
+        .map(|categories| HttpResponse::Ok().json(categories))
+        .map_err(ApiError::from)
+}
+
+async fn post_category(
+    db: web::Data<db::GestureClientPool>,
+    new_category: web::Json<NewCategory>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::post_category(
+        &db,
+        new_category.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|id| HttpResponse::Created().body(id))
+    .map_err(ApiError::from)
+}
+
+async fn put_category(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    category: web::Json<NewCategory>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_category(
+        &db,
+        &id,
+        category.into_inner(),
         &conf.hs256_private_key,
         credentials.token(),
     )
@@ -387,3 +903,238 @@ async fn delete_picture(
     .map(|_| HttpResponse::Created().finish())
     .map_err(ApiError::from)
 }
+
+async fn delete_category(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_category(&db, &id, &conf.hs256_private_key, credentials.token())
+        .await
+        .map(|_| HttpResponse::Created().finish())
+        .map_err(ApiError::from)
+}
+
+async fn put_gesture_categories(
+    db: web::Data<db::GestureClientPool>,
+    id: web::Path<String>,
+    gesture_categories: web::Json<GestureCategories>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_gesture_categories(
+        &db,
+        &id,
+        gesture_categories.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn put_related_gesture(
+    db: web::Data<db::GestureClientPool>,
+    path: web::Path<(String, String)>,
+    relation: web::Json<GestureRelation>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    let (id, id_related) = path.into_inner();
+    handlers::put_related_gesture(
+        &db,
+        &id,
+        &id_related,
+        relation.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn delete_related_gesture(
+    db: web::Data<db::GestureClientPool>,
+    path: web::Path<(String, String)>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    let (id, id_related) = path.into_inner();
+    handlers::delete_related_gesture(
+        &db,
+        &id,
+        &id_related,
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn get_languages(
+    db: web::Data<db::GestureClientPool>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::get_languages(&db)
+        .await
+        .map(|languages| HttpResponse::Ok().json(languages))
+        .map_err(ApiError::from)
+}
+
+async fn post_language(
+    db: web::Data<db::GestureClientPool>,
+    language: web::Json<Language>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::post_language(
+        &db,
+        language.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|code| HttpResponse::Created().body(code))
+    .map_err(ApiError::from)
+}
+
+async fn put_language(
+    db: web::Data<db::GestureClientPool>,
+    code: web::Path<String>,
+    language_name: web::Json<LanguageName>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::put_language(
+        &db,
+        &code,
+        language_name.into_inner(),
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|_| HttpResponse::Created().finish())
+    .map_err(ApiError::from)
+}
+
+async fn delete_language(
+    db: web::Data<db::GestureClientPool>,
+    code: web::Path<String>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    handlers::delete_language(&db, &code, &conf.hs256_private_key, credentials.token())
+        .await
+        .map(|_| HttpResponse::Created().finish())
+        .map_err(ApiError::from)
+}
+
+/// Multipart body: the `file` part is the import file, other parts are pictures named by their path in it
+async fn post_import(
+    files: Multipart,
+    db: web::Data<db::GestureClientPool>,
+    storage: web::Data<mon_oeil_storage::Storage>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+    import_param: web::Query<ImportParam>,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    let ImportParam { format, dry_run } = import_param.into_inner();
+    let ImportFiles { file, pictures } = match extract_import_files(files).await {
+        Ok(files) => files,
+        Err(res) => return Ok(res),
+    };
+    let (file_name, content) = match file {
+        Some(file) => file,
+        _ => return Ok(HttpResponse::BadRequest().body("File not found")),
+    };
+    let format = match format.or_else(|| file_name.as_deref().and_then(ImportFormat::from_path)) {
+        Some(format) => format,
+        _ => return Ok(HttpResponse::BadRequest().body("Unknown format, use json, csv or yaml")),
+    };
+
+    handlers::import_gestures(
+        &db,
+        &storage,
+        format,
+        &content,
+        pictures,
+        dry_run,
+        &conf.hs256_private_key,
+        credentials.token(),
+    )
+    .await
+    .map(|report| {
+        if !report.errors.is_empty() {
+            HttpResponse::BadRequest().json(report)
+        } else if report.dry_run {
+            HttpResponse::Ok().json(report)
+        } else {
+            HttpResponse::Created().json(report)
+        }
+    })
+    .map_err(ApiError::from)
+}
+
+struct ImportFiles {
+    /// Name and content of the import file
+    file: Option<(Option<String>, Vec<u8>)>,
+    /// Content of the other files by part name
+    pictures: HashMap<String, Vec<u8>>,
+}
+
+async fn extract_import_files(mut files: Multipart) -> Result<ImportFiles, HttpResponse> {
+    let mut file = None;
+    let mut pictures = HashMap::new();
+
+    while let Ok(Some(mut field)) = files.try_next().await {
+        let mut content = BytesMut::new();
+        while let Some(chunk) = field.next().await {
+            match chunk {
+                Ok(chunk) => content.put(chunk),
+                _ => return Err(HttpResponse::BadRequest().body("File corrupted")),
+            }
+        }
+        let content = content.freeze().to_vec();
+
+        let disposition = field.content_disposition();
+        let name = disposition
+            .as_ref()
+            .and_then(|disposition| disposition.get_name())
+            .unwrap_or_default()
+            .to_owned();
+        if name == "file" {
+            let file_name = disposition
+                .as_ref()
+                .and_then(|disposition| disposition.get_filename())
+                .map(str::to_owned);
+            file = Some((file_name, content));
+        } else {
+            pictures.insert(name, content);
+        }
+    }
+
+    Ok(ImportFiles { file, pictures })
+}
+
+async fn get_export(
+    db: web::Data<db::GestureClientPool>,
+    storage: web::Data<mon_oeil_storage::Storage>,
+    conf: web::Data<Conf>,
+    credentials: BearerAuth,
+) -> Result<HttpResponse, ApiError<mon_oeil_core::Error>> {
+    let export =
+        handlers::export_gestures(&db, &storage, &conf.hs256_private_key, credentials.token())
+            .await?;
+    let archive = archive_stream(export, storage.into_inner()).map(|chunk| chunk.map_err(ApiError));
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-tar")
+        .header(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"mon_oeil.tar\"",
+        )
+        .streaming(Box::pin(archive)))
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use mon_oeil_core::*;
+
+const USAGE: &str = "Usage: mon_oeil_srv import <file.json|csv|yaml> [--dry-run] [--author <name>]";
+
+/// `import` subcommand, picture paths are relative to the import file
+/// Return the exit code: 0 when everything was imported or checked, 1 otherwise
+pub async fn run_import(args: &[String]) -> i32 {
+    let mut path = None;
+    let mut dry_run = false;
+    let mut author = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--author" => author = args.next().cloned(),
+            _ if path.is_none() => path = Some(arg.as_str()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return 1;
+            }
+        }
+    }
+    let path = match path {
+        Some(path) => Path::new(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            return 1;
+        }
+    };
+
+    let format = match path.to_str().and_then(ImportFormat::from_path) {
+        Some(format) => format,
+        _ => {
+            eprintln!(
+                "Unknown format of {}, use json, csv or yaml",
+                path.display()
+            );
+            return 1;
+        }
+    };
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Can't read {}: {}", path.display(), e);
+            return 1;
+        }
+    };
+    let gestures = match handlers::parse_import(format, &content) {
+        Ok(gestures) => gestures,
+        Err(errors) => {
+            print_errors(&errors);
+            return 1;
+        }
+    };
+
+    // missing files are reported by the import
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let files: HashMap<String, Vec<u8>> = gestures
+        .iter()
+        .flat_map(|gesture| &gesture.pictures)
+        .filter_map(|picture| {
+            std::fs::read(dir.join(&picture.path))
+                .ok()
+                .map(|content| (picture.path.clone(), content))
+        })
+        .collect();
+
+    let db_pool = match mon_oeil_db::connect_db() {
+        Ok(db_pool) => db_pool,
+        Err(e) => {
+            eprintln!("Database configuration failed: {}", e);
+            return 1;
+        }
+    };
+    let storage = crate::build_storage();
+
+    match handlers::import_gestures_as(&db_pool, &storage, gestures, files, dry_run, author).await {
+        Ok(report) if report.errors.is_empty() => {
+            println!(
+                "{} {} gestures, {} descriptions, {} meanings and {} pictures",
+                if dry_run { "Would import" } else { "Imported" },
+                report.gestures,
+                report.descriptions,
+                report.meanings,
+                report.pictures
+            );
+            0
+        }
+        Ok(report) => {
+            print_errors(&report.errors);
+            1
+        }
+        Err(e) => {
+            eprintln!(
+                "Import failed, nothing was imported and uploaded pictures were deleted unless logged otherwise: {:?}",
+                e
+            );
+            1
+        }
+    }
+}
+
+fn print_errors(errors: &[ImportError]) {
+    for error in errors {
+        eprintln!("{}: {}", error.location, error.message);
+    }
+    eprintln!("{} errors, nothing was imported", errors.len());
+}
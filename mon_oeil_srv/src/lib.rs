@@ -1,14 +1,23 @@
 use actix_files::NamedFile;
-use actix_web::{dev::Server, middleware::Logger, web, App, HttpRequest, HttpServer, Result};
+use actix_web::{
+    dev::Server, http::StatusCode, middleware::Logger, web, App, HttpRequest, HttpResponse,
+    HttpServer, Result,
+};
+use serde::Serialize;
 use std::net::TcpListener;
 use std::path::PathBuf;
 
+pub mod archive;
 pub mod auth;
 pub mod core;
+pub mod import;
 use mon_oeil_storage::*;
 
-pub fn run(listener: TcpListener) -> Result<Server, std::io::Error> {
-    run_with_storage(listener, build_storage)
+pub fn run(
+    listener: TcpListener,
+    db_pool: mon_oeil_db::GestureClientPool,
+) -> Result<Server, std::io::Error> {
+    run_with_storage(listener, db_pool, build_storage)
 }
 
 fn build_storage() -> Storage {
@@ -20,12 +29,17 @@ fn build_storage() -> Storage {
 
 pub fn run_with_storage(
     listener: TcpListener,
+    db_pool: mon_oeil_db::GestureClientPool,
     build_storage: fn() -> Storage,
 ) -> Result<Server, std::io::Error> {
     let hs256_private_key = std::env::var("HS256_PRIVATE_KEY").unwrap();
     let salt_hash = std::env::var("SALT_HASH").unwrap();
-
-    let db_pool = mon_oeil_db::connect_db();
+    let fallback_langs: Vec<String> = std::env::var("FALLBACK_LANGS")
+        .unwrap_or_else(|_| "fr".to_owned())
+        .split(',')
+        .map(|lang| lang.trim().to_lowercase())
+        .filter(|lang| !lang.is_empty())
+        .collect();
 
     let server = HttpServer::new(move || {
         App::new()
@@ -36,6 +50,7 @@ pub fn run_with_storage(
             .data(Conf {
                 hs256_private_key: hs256_private_key.to_owned(),
                 salt_hash: salt_hash.to_owned(),
+                fallback_langs: fallback_langs.clone(),
             })
             .configure(|mut config| {
                 auth::app_config(&mut config);
@@ -67,6 +82,21 @@ async fn vue() -> Result<NamedFile> {
 pub struct Conf {
     pub hs256_private_key: String,
     pub salt_hash: String,
+    /// Langs of the content served when none of the asked ones is there
+    pub fallback_langs: Vec<String>,
 }
 
 struct ApiError<T>(T);
+
+/// Body of every api error response
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+    message: String,
+}
+
+fn json_error(status: StatusCode, error: &str, message: String) -> actix_web::Error {
+    HttpResponse::build(status)
+        .json(ErrorBody { error, message })
+        .into()
+}
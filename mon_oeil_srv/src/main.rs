@@ -1,4 +1,9 @@
-use mon_oeil_srv::run;
+use log::error;
+use mon_oeil_srv::{
+    archive::{run_export, run_restore},
+    import::run_import,
+    run,
+};
 use std::net::TcpListener;
 
 #[actix_rt::main]
@@ -6,11 +11,31 @@ async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
     env_logger::init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("import") => std::process::exit(run_import(&args[1..]).await),
+        Some("export") => std::process::exit(run_export(&args[1..]).await),
+        Some("restore") => std::process::exit(run_restore(&args[1..]).await),
+        _ => {}
+    }
+
     let listener = TcpListener::bind(format!(
         "0.0.0.0:{}",
         std::env::var("PORT").expect("Need env var PORT")
     ))
     .expect("Failed to bind random port");
 
-    run(listener)?.await
+    let db_pool = match mon_oeil_db::connect_db() {
+        Ok(db_pool) => db_pool,
+        Err(e) => {
+            error!("Database configuration failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = db_pool.check_schema().await {
+        error!("Database check failed: {}", e);
+        std::process::exit(1);
+    }
+
+    run(listener, db_pool)?.await
 }
@@ -0,0 +1,349 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+use std::collections::BTreeMap;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+const GREETINGS: &str = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01";
+const HELLO: &str = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a02";
+const INSULTS: &str = "5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a03";
+
+fn insert_categories() {
+    let mut client = setup::connect();
+    client
+        .batch_execute(&format!(
+            "INSERT INTO categories(id_category, id_parent, position) VALUES
+                ('{greetings}', NULL, 0), ('{hello}', '{greetings}', 0), ('{insults}', NULL, 1);
+            INSERT INTO category_labels(id_category, lang, label) VALUES
+                ('{greetings}', 'fr', 'Salutations'), ('{greetings}', 'en', 'Greetings'),
+                ('{hello}', 'fr', 'Bonjour'), ('{insults}', 'fr', 'Insultes');",
+            greetings = GREETINGS,
+            hello = HELLO,
+            insults = INSULTS
+        ))
+        .unwrap();
+}
+
+fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+    labels
+        .iter()
+        .map(|(lang, label)| ((*lang).to_owned(), (*label).to_owned()))
+        .collect()
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_categories_should_nest_ordered_categories() {
+    setup::reset_db();
+    insert_categories();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/categories", address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let categories: Vec<Category> = res.json().await.unwrap();
+    assert_eq!(
+        categories,
+        vec![
+            Category {
+                id: GREETINGS.to_owned(),
+                position: 0,
+                labels: labels(&[("en", "Greetings"), ("fr", "Salutations")]),
+                children: vec![Category {
+                    id: HELLO.to_owned(),
+                    position: 0,
+                    labels: labels(&[("fr", "Bonjour")]),
+                    children: vec![],
+                }],
+            },
+            Category {
+                id: INSULTS.to_owned(),
+                position: 1,
+                labels: labels(&[("fr", "Insultes")]),
+                children: vec![],
+            },
+        ]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_category_should_reject_unauth() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/categories", address))
+        .json(&NewCategory {
+            parent: None,
+            position: 0,
+            labels: labels(&[("fr", "Émotions")]),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_category_should_add_it_below_its_parent() {
+    setup::reset_db();
+    insert_categories();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/categories", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewCategory {
+            parent: Some(GREETINGS.to_owned()),
+            position: 1,
+            labels: labels(&[("FR", "Au revoir "), ("en-gb", "Goodbye")]),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let id = res.text().await.unwrap();
+
+    let res = client
+        .get(&format!("{}/categories", address))
+        .send()
+        .await
+        .unwrap();
+    let categories: Vec<Category> = res.json().await.unwrap();
+    assert_eq!(
+        categories[0].children[1],
+        Category {
+            id,
+            position: 1,
+            labels: labels(&[("en-GB", "Goodbye"), ("fr", "Au revoir")]),
+            children: vec![],
+        }
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_category_should_reject_invalid_labels_or_parent() {
+    setup::reset_db();
+    insert_categories();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    for (parent, category_labels, status) in &[
+        (None, labels(&[]), StatusCode::BAD_REQUEST),
+        (None, labels(&[("fr", " ")]), StatusCode::BAD_REQUEST),
+        (None, labels(&[("xx", "Émotions")]), StatusCode::BAD_REQUEST),
+        (
+            Some("ce27c124-e47b-490f-b8fe-3f37d5dbbef6"),
+            labels(&[("fr", "Émotions")]),
+            StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let res = client
+            .post(&format!("{}/categories", address))
+            .header("Authorization", setup::ADMIN_TOKEN)
+            .json(&NewCategory {
+                parent: parent.map(str::to_owned),
+                position: 0,
+                labels: category_labels.clone(),
+            })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), *status, "{:?} {:?}", parent, category_labels);
+    }
+
+    let count: i64 = setup::connect()
+        .query_one("SELECT COUNT(*) FROM categories", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 3);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_category_should_not_move_it_below_itself() {
+    setup::reset_db();
+    insert_categories();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/categories/{}", address, GREETINGS))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewCategory {
+            parent: Some(HELLO.to_owned()),
+            position: 0,
+            labels: labels(&[("fr", "Salutations")]),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .put(&format!("{}/categories/{}", address, HELLO))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewCategory {
+            parent: Some(INSULTS.to_owned()),
+            position: 2,
+            labels: labels(&[("en", "Hello")]),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .get(&format!("{}/categories", address))
+        .send()
+        .await
+        .unwrap();
+    let categories: Vec<Category> = res.json().await.unwrap();
+    assert!(categories[0].children.is_empty());
+    assert_eq!(
+        categories[1].children,
+        vec![Category {
+            id: HELLO.to_owned(),
+            position: 2,
+            labels: labels(&[("en", "Hello")]),
+            children: vec![],
+        }]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn gestures_should_be_filtered_by_category_and_its_subcategories() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    insert_categories();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/categories",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&GestureCategories {
+            categories: vec![HELLO.to_owned()],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .get(&format!("{}/gestures?category={}", address, GREETINGS))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+    assert_eq!(gestures[0].categories, vec![HELLO.to_owned()]);
+
+    let res = client
+        .get(&format!("{}/gestures?category={}", address, INSULTS))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert!(gestures.is_empty());
+
+    let res = client
+        .get(&format!("{}/gestures?category=greetings", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_gesture_categories_should_reject_unknown_category() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/categories",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&GestureCategories {
+            categories: vec![GREETINGS.to_owned()],
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_category_should_remove_subcategories_and_keep_gestures() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+    insert_categories();
+    setup::connect()
+        .execute(
+            &*format!(
+                "INSERT INTO gesture_categories(id_gesture, id_category)
+                VALUES ('ce27c124-e47b-490f-b8fe-3f37d5dbbef6', '{}')",
+                HELLO
+            ),
+            &[],
+        )
+        .unwrap();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .delete(&format!("{}/categories/{}", address, GREETINGS))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let mut db = setup::connect();
+    let categories: i64 = db
+        .query_one("SELECT COUNT(*) FROM categories", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(categories, 1);
+    let gestures: i64 = db
+        .query_one("SELECT COUNT(*) FROM gestures", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(gestures, 1);
+}
@@ -17,7 +17,7 @@ async fn post_description_should_reject_unauth() {
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -43,7 +43,7 @@ async fn post_description_should_accept_auth() {
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -70,7 +70,7 @@ async fn post_description_on_non_existing_gesture_should_fail() {
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -85,6 +85,36 @@ async fn post_description_on_non_existing_gesture_should_fail() {
         .await
         .unwrap();
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "not_found");
+    assert_eq!(body["message"], "The gesture does not exist");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_description_on_invalid_gesture_id_should_be_rejected() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let new_description = NewDescription {
+        value: "Une petite description".to_owned(),
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/gestures/not-an-id/descriptions", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&new_description)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "bad_request");
 }
 
 #[actix_rt::test]
@@ -97,7 +127,7 @@ async fn post_description_should_return_new_uuid() {
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -131,7 +161,7 @@ async fn post_gestures_should_return_uuid_random() {
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -179,7 +209,7 @@ async fn get_gestures_after_post_description_should_return_gesture_with_posted_d
 
     let new_description = NewDescription {
         value: "Une petite description".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -210,15 +240,21 @@ async fn get_gestures_after_post_description_should_return_gesture_with_posted_d
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![Description {
                 id: uuid,
+                version: 1,
                 value: "Une petite description".to_owned(),
-                langs: vec!["fr".to_owned(), "us".to_owned()],
-                meanings: vec![]
+                langs: vec!["fr".to_owned(), "en".to_owned()],
+                meanings: vec![],
+                translation_group: None,
+                source: None,
             }],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -258,6 +294,7 @@ async fn delete_description_should_accept_auth() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -278,6 +315,7 @@ async fn delete_not_existing_description_should_fail() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -300,6 +338,7 @@ async fn get_gestures_after_delete_description_should_return_nothing() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -317,10 +356,13 @@ async fn get_gestures_after_delete_description_should_return_nothing() {
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -336,7 +378,7 @@ async fn put_description_should_reject_unauth() {
 
     let new_description = NewDescription {
         value: "Une autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -363,7 +405,7 @@ async fn put_description_should_accept_auth() {
 
     let new_description = NewDescription {
         value: "Une autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -374,6 +416,7 @@ async fn put_description_should_accept_auth() {
         ))
         .json(&new_description)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -390,7 +433,7 @@ async fn put_not_existing_description_should_fail() {
 
     let new_description = NewDescription {
         value: "Une autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -401,6 +444,7 @@ async fn put_not_existing_description_should_fail() {
         ))
         .json(&new_description)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -418,7 +462,7 @@ async fn get_gesture_after_put_should_return_updated_gesture() {
 
     let new_description = NewDescription {
         value: "Une autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -429,6 +473,7 @@ async fn get_gesture_after_put_should_return_updated_gesture() {
         ))
         .json(&new_description)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -446,16 +491,147 @@ async fn get_gesture_after_put_should_return_updated_gesture() {
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![Description {
                 id: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+                version: 2,
                 value: "Une autre".to_owned(),
-                langs: vec!["kr".to_owned()],
-                meanings: vec![]
+                langs: vec!["de".to_owned()],
+                meanings: vec![],
+                translation_group: None,
+                source: None,
             }],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
 }
+
+#[actix_rt::test]
+#[serial]
+async fn put_description_without_if_match_should_be_rejected() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let new_description = NewDescription {
+        value: "Une autre".to_owned(),
+        langs: vec!["de".to_owned()],
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .json(&new_description)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::PRECONDITION_REQUIRED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_description_should_return_new_version_as_etag() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let new_description = NewDescription {
+        value: "Une autre".to_owned(),
+        langs: vec!["de".to_owned()],
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .json(&new_description)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .send()
+        .await
+        .unwrap();
+
+    assert!(res.status().is_success());
+    assert_eq!(res.headers()["etag"], "\"2\"");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_description_with_stale_version_should_fail() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let new_description = NewDescription {
+        value: "Une autre".to_owned(),
+        langs: vec!["de".to_owned()],
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .json(&new_description)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .json(&new_description)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_description_with_stale_version_should_fail() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .delete(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"3\"")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+}
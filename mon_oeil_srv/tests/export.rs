@@ -0,0 +1,330 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+mod utils;
+
+use mon_oeil_core::*;
+use mon_oeil_storage::*;
+use utils::setup;
+
+#[actix_rt::test]
+#[serial]
+async fn get_export_should_reject_unauth() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/export", address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_export_then_restore_should_bring_back_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    setup::connect()
+        .batch_execute(
+            "INSERT INTO languages(code, name) VALUES ('wa', 'Walon');
+            INSERT INTO categories(id_category, id_parent, position) VALUES
+                ('5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01', NULL, 0),
+                ('5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a02', '5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01', 3);
+            INSERT INTO category_labels(id_category, lang, label) VALUES
+                ('5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01', 'fr', 'Salutations'),
+                ('5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a02', 'wa', 'Bondjou');
+            INSERT INTO gesture_categories(id_gesture, id_category) VALUES
+                ('ce27c124-e47b-490f-b8fe-3f37d5dbbef6', '5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a02');",
+        )
+        .unwrap();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_download()
+            .times(3)
+            .returning(|id, _| Ok(id.as_bytes().to_vec()));
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/export", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.bytes().await.unwrap();
+
+    let mut languages: Vec<Language> = vec![];
+    let mut categories: Vec<Category> = vec![];
+    let mut exported: Vec<Gesture> = vec![];
+    let mut creation_dates = BTreeMap::new();
+    let mut files = vec![];
+    let mut archive = tar::Archive::new(&body[..]);
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().into_owned();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).unwrap();
+        if path == "languages.json" {
+            languages = serde_json::from_slice(&content).unwrap();
+        } else if path == "categories.json" {
+            categories = serde_json::from_slice(&content).unwrap();
+        } else if path == "gestures.json" {
+            exported = serde_json::from_slice(&content).unwrap();
+        } else if path == "creation_dates.json" {
+            creation_dates = serde_json::from_slice(&content).unwrap();
+        } else {
+            let file = PictureFile::from_path(&path).unwrap();
+            assert_eq!(file.id.as_bytes(), &content[..]);
+            files.push((file, content));
+        }
+    }
+    assert_eq!(exported.len(), 2);
+    assert_eq!(files.len(), 3);
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let before: Vec<Gesture> = res.json().await.unwrap();
+    let categories_before = handlers::get_categories(&setup::CONF.db_pool)
+        .await
+        .unwrap();
+
+    setup::reset_db();
+    let mut storage = Storage::default();
+    storage.expect_upload().times(3).returning(|_, _, _| Ok(()));
+    handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &storage,
+        languages,
+        categories,
+        exported,
+        creation_dates,
+        files,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let after: Vec<Gesture> = res.json().await.unwrap();
+    let ids = |gestures: &[Gesture]| gestures.iter().map(|g| g.id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&after), ids(&before));
+    assert_eq!(after[0].descriptions.len(), before[0].descriptions.len());
+    assert_eq!(after[0].pictures.len(), before[0].pictures.len());
+    assert_eq!(after[0].categories, before[0].categories);
+    assert_eq!(
+        handlers::get_categories(&setup::CONF.db_pool)
+            .await
+            .unwrap(),
+        categories_before
+    );
+    let languages = handlers::get_languages(&setup::CONF.db_pool).await.unwrap();
+    assert!(languages.iter().any(|language| language.code == "wa"));
+}
+
+/// Exported gesture with the picture of `setup::insert_gesture_with_picture`
+fn gesture_with_picture() -> Gesture {
+    Gesture {
+        id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+        version: 1,
+        tags: vec!["tag1".to_owned()],
+        descriptions: vec![],
+        meanings: vec![],
+        pictures: vec![Picture {
+            id: "283e7b04-7c13-4154-aafe-8e55b6960fe3".to_owned(),
+            version: 1,
+            langs: vec!["fr".to_owned()],
+            url: "http://monoielfakeapp.com/283e7b04-7c13-4154-aafe-8e55b6960fe3.png".to_owned(),
+            source: None,
+        }],
+        categories: vec![],
+        related: vec![],
+        matched_in: None,
+        snippet: None,
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn restore_should_reject_missing_picture_files() {
+    setup::reset_db();
+
+    let res = handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &Storage::default(),
+        vec![],
+        vec![],
+        vec![gesture_with_picture()],
+        BTreeMap::new(),
+        vec![],
+        None,
+    )
+    .await;
+
+    assert!(matches!(res, Err(Error::NotAccepted(_))));
+    let count: i64 = setup::connect()
+        .query_one("SELECT COUNT(*) FROM gestures", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn restore_should_reject_existing_pictures_before_uploading() {
+    setup::reset_db();
+    setup::insert_gesture_with_picture();
+
+    let file = PictureFile::from_path("pictures/283e7b04-7c13-4154-aafe-8e55b6960fe3.png").unwrap();
+    let res = handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &Storage::default(),
+        vec![],
+        vec![],
+        vec![gesture_with_picture()],
+        BTreeMap::new(),
+        vec![(file, b"picture".to_vec())],
+        None,
+    )
+    .await;
+
+    assert!(matches!(res, Err(Error::NotAccepted(_))));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn restore_should_delete_uploaded_pictures_when_a_gesture_exists() {
+    setup::reset_db();
+    setup::insert_gesture_with_picture();
+
+    let mut storage = Storage::default();
+    storage.expect_upload().times(1).returning(|_, _, _| Ok(()));
+    storage
+        .expect_delete()
+        .withf(|id, fmt| id == "0b4d3b7e-56b5-4f0e-9a8c-2f51a0e6d7c1" && fmt == "png")
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    let mut gesture = gesture_with_picture();
+    gesture.pictures[0].id = "0b4d3b7e-56b5-4f0e-9a8c-2f51a0e6d7c1".to_owned();
+    let file = PictureFile::from_path("pictures/0b4d3b7e-56b5-4f0e-9a8c-2f51a0e6d7c1.png").unwrap();
+    let res = handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &storage,
+        vec![],
+        vec![],
+        vec![gesture],
+        BTreeMap::new(),
+        vec![(file, b"picture".to_vec())],
+        None,
+    )
+    .await;
+
+    assert!(res.is_err());
+}
+
+#[actix_rt::test]
+#[serial]
+async fn restore_should_reject_unknown_categories() {
+    setup::reset_db();
+
+    let mut gesture = gesture_with_picture();
+    gesture.pictures = vec![];
+    gesture.categories = vec!["5b2a5c3e-4a7f-4b4c-9d52-2f0c6b0c1a01".to_owned()];
+    let res = handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &Storage::default(),
+        vec![],
+        vec![],
+        vec![gesture],
+        BTreeMap::new(),
+        vec![],
+        None,
+    )
+    .await;
+
+    assert!(res.is_err());
+    let count: i64 = setup::connect()
+        .query_one("SELECT COUNT(*) FROM gestures", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn restore_should_keep_creation_dates_and_versions() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    // dates in the order of the ids rather than of the inserts, and versions past 1
+    setup::connect()
+        .batch_execute(
+            "UPDATE gestures SET version = 3, creation_date = TIMESTAMP '2020-01-01'
+                + ('x' || substr(id_gesture::text, 1, 6))::bit(24)::int * INTERVAL '1 second';
+            UPDATE descriptions SET version = 4, creation_date = TIMESTAMP '2020-01-01'
+                + ('x' || substr(id_description::text, 1, 6))::bit(24)::int * INTERVAL '1 second';
+            UPDATE meanings SET version = 5, creation_date = TIMESTAMP '2020-01-01'
+                + ('x' || substr(id_meaning::text, 1, 6))::bit(24)::int * INTERVAL '1 second';
+            UPDATE pictures SET version = 6, creation_date = TIMESTAMP '2020-01-01'
+                + ('x' || substr(id_picture::text, 1, 6))::bit(24)::int * INTERVAL '1 second';",
+        )
+        .unwrap();
+
+    let mut storage = Storage::default();
+    storage
+        .expect_get_url()
+        .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+    storage.expect_upload().times(3).returning(|_, _, _| Ok(()));
+    let before = handlers::export_all_gestures(&setup::CONF.db_pool, &storage)
+        .await
+        .unwrap();
+
+    setup::reset_db();
+    let files = before
+        .pictures
+        .iter()
+        .map(|file| (file.clone(), b"picture".to_vec()))
+        .collect();
+    handlers::restore_gestures(
+        &setup::CONF.db_pool,
+        &storage,
+        before.languages.clone(),
+        before.categories.clone(),
+        before.gestures.clone(),
+        before.creation_dates.clone(),
+        files,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let after = handlers::export_all_gestures(&setup::CONF.db_pool, &storage)
+        .await
+        .unwrap();
+    assert_eq!(after.gestures, before.gestures);
+    assert_eq!(after.creation_dates, before.creation_dates);
+    assert_eq!(after.gestures[0].version, 3);
+    assert_eq!(after.gestures[0].pictures[0].version, 6);
+}
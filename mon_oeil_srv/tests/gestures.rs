@@ -176,10 +176,15 @@ async fn get_gestures_after_post_gestures_should_return_posted_gesture() {
     assert_eq!(
         vec![Gesture {
             id: uuid,
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -215,79 +220,129 @@ async fn get_gestures_with_full_links() {
         vec![
             Gesture {
                 id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+                version: 1,
                 tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+                matched_in: None,
+                snippet: None,
                 descriptions: vec![
                     Description {
                         id: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+                        version: 1,
                         value: "Une petite description".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
                         meanings: vec![
                             Meaning {
                                 id: "e2c6eee0-49a7-49c4-9a0f-a9c6e6f668d8".to_owned(),
+                                version: 1,
                                 value: "Un petit meaning".to_owned(),
                                 langs: vec!["fr".to_owned(), "us".to_owned()],
+                                translation_group: None,
+                                regions: vec![],
+                                register: None,
+                                source: None,
                             },
                             Meaning {
                                 id: "45dca590-6bc4-4e4b-ad0c-0fe57a3a9643".to_owned(),
+                                version: 1,
                                 value: "Un petit meaning".to_owned(),
                                 langs: vec!["fr".to_owned(), "us".to_owned()],
+                                translation_group: None,
+                                regions: vec![],
+                                register: None,
+                                source: None,
                             },
                         ],
+                        translation_group: None,
+                        source: None,
                     },
                     Description {
                         id: "1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28".to_owned(),
+                        version: 1,
                         value: "Une petite description".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
                         meanings: vec![],
+                        translation_group: None,
+                        source: None,
                     },
                 ],
                 meanings: vec![
                     Meaning {
                         id: "59c25147-021e-4584-9c35-97cbf060cc89".to_owned(),
+                        version: 1,
                         value: "Un petit meaning".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
+                        translation_group: None,
+                        regions: vec![],
+                        register: None,
+                        source: None,
                     },
                     Meaning {
                         id: "02ca8fb9-c56e-4e45-b13e-98a6732f780a".to_owned(),
+                        version: 1,
                         value: "Un petit meaning".to_owned(),
                         langs: vec!["fr".to_owned(), "us".to_owned()],
+                        translation_group: None,
+                        regions: vec![],
+                        register: None,
+                        source: None,
                     },
                 ],
                 pictures: vec![
                     Picture {
                         id: "283e7b04-7c13-4154-aafe-8e55b6960fe3".to_owned(),
+                        version: 1,
                         langs: vec!["fr".to_owned(), "us".to_owned()],
                         url: "http://monoielfakeapp.com/283e7b04-7c13-4154-aafe-8e55b6960fe3.png"
                             .to_owned(),
+                        source: None,
                     },
                     Picture {
                         id: "03b9bfc6-fa22-4ffb-9464-93c1be842ace".to_owned(),
+                        version: 1,
                         langs: vec!["fr".to_owned(), "us".to_owned()],
                         url: "http://monoielfakeapp.com/03b9bfc6-fa22-4ffb-9464-93c1be842ace.png"
                             .to_owned(),
+                        source: None,
                     },
                 ],
+                categories: vec![],
+                related: vec![],
             },
             Gesture {
                 id: "16991982-1752-4aa0-bb22-db3fbceb3780".to_owned(),
+                version: 1,
                 tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+                matched_in: None,
+                snippet: None,
                 descriptions: vec![Description {
                     id: "cdbcd8fb-3d6d-4f09-86ba-37a6ec1dd293".to_owned(),
+                    version: 1,
                     value: "Une petite description".to_owned(),
                     langs: vec!["fr".to_owned(), "us".to_owned()],
                     meanings: vec![],
+                    translation_group: None,
+                    source: None,
                 }],
                 meanings: vec![Meaning {
                     id: "4719b1d7-2810-4f7d-865d-03ee44cf0add".to_owned(),
+                    version: 1,
                     value: "Un petit meaning".to_owned(),
                     langs: vec!["fr".to_owned(), "us".to_owned()],
+                    translation_group: None,
+                    regions: vec![],
+                    register: None,
+                    source: None,
                 }],
                 pictures: vec![Picture {
                     id: "6e1ee88d-fd97-488c-9aa8-6b66a3f3e714".to_owned(),
+                    version: 1,
                     langs: vec!["fr".to_owned(), "us".to_owned()],
                     url: "http://monoielfakeapp.com/6e1ee88d-fd97-488c-9aa8-6b66a3f3e714.png"
                         .to_owned(),
+                    source: None,
                 }],
+                categories: vec![],
+                related: vec![],
             },
         ]
     )
@@ -339,6 +394,7 @@ async fn put_gesture_should_accept_auth() {
         ))
         .json(&updatable_gesture)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -365,6 +421,7 @@ async fn put_not_existing_gesture_should_fail() {
         ))
         .json(&updatable_gesture)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -392,6 +449,7 @@ async fn get_gesture_after_put_should_return_updated_gesture() {
         ))
         .json(&updatable_gesture)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -409,10 +467,15 @@ async fn get_gesture_after_put_should_return_updated_gesture() {
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 2,
             tags: vec!["tag3".to_owned(), "tag4".to_owned()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -586,3 +649,1030 @@ async fn get_gestures_with_search_match_on_meaning() {
     assert!(gestures.len() == 1);
     assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
 }
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_stem_with_text_lang() {
+    setup::reset_db();
+    setup::insert_gesture_with_french_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=cheval", address))
+        .send()
+        .await
+        .unwrap();
+
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_and_lang_should_only_match_this_lang() {
+    setup::reset_db();
+    setup::insert_gesture_with_french_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=cheval&lang=fr", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+
+    let res = client
+        .get(&format!("{}/gestures?search=cheval&lang=en", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures, vec![]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_accept_any_input() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .query(&[("search", "(bras | & !\"tag1 -jambe")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .query(&[("search", "tag1 -jambe")])
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_exclude_gestures_with_excluded_word_anywhere() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    // both gestures are tagged tag1, the other one has kitchen in a description
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .query(&[("search", "tag1 -kitchen")])
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_ignore_accents_and_case() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .query(&[("search", "Déhors")])
+        .send()
+        .await
+        .unwrap();
+
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_find_close_texts() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=papilon", address))
+        .send()
+        .await
+        .unwrap();
+
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_rank_close_texts_after_matches() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=tag1%20bras", address))
+        .send()
+        .await
+        .unwrap();
+
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 2);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+    assert_eq!(gestures[1].id, "16991982-1752-4aa0-bb22-db3fbceb3780");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_tags_should_return_gestures_having_all_tags() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?tags=tag1,bras", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+
+    let res = client
+        .get(&format!("{}/gestures?tags=tag1", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 2);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_and_tags_should_match_both() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=tag1&tags=jambe", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "16991982-1752-4aa0-bb22-db3fbceb3780");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_has_pictures_should_filter_on_pictures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    setup::connect()
+        .execute(
+            "INSERT INTO gestures(id_gesture, tags) VALUES ('9b9f3c55-3b46-4c6e-8b0e-0c63c4f6b0a1', '{\"nu\"}')",
+            &[],
+        )
+        .unwrap();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?has_pictures=false", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "9b9f3c55-3b46-4c6e-8b0e-0c63c4f6b0a1");
+
+    let res = client
+        .get(&format!("{}/gestures?has_pictures=true", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 2);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_lang_should_return_gestures_with_content_in_lang() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?lang=fr", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 2);
+
+    let res = client
+        .get(&format!("{}/gestures?lang=de", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures, vec![]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_lang_should_ignore_its_case() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    setup::connect()
+        .execute(
+            "INSERT INTO meanings(id_meaning, id_gesture, val, langs)
+            VALUES ('3b4d1a6c-3f5e-4c9a-9b0e-6c1d2e3f4a5b', '16991982-1752-4aa0-bb22-db3fbceb3780', 'Better late than never', '{\"en-GB\"}')",
+            &[],
+        )
+        .unwrap();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?lang=en-gb", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, "16991982-1752-4aa0-bb22-db3fbceb3780");
+
+    let res = client
+        .get(&format!("{}/gestures/random?lang=EN-GB", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .get(&format!("{}/gestures?lang=us", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+fn insert_german_meaning() {
+    setup::connect()
+        .execute(
+            "INSERT INTO meanings(id_meaning, id_gesture, val, langs)
+            VALUES ('3b4d1a6c-3f5e-4c9a-9b0e-6c1d2e3f4a5b', '16991982-1752-4aa0-bb22-db3fbceb3780', 'Besser spät als nie', '{\"de\"}')",
+            &[],
+        )
+        .unwrap();
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_accept_language_should_narrow_content_to_preferred_lang() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    insert_german_meaning();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?negotiate=true", address))
+        .header("Accept-Language", "fr;q=0.5, de-DE, *;q=0.1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["content-language"], "fr, de");
+    assert_eq!(res.headers()["vary"], "Accept-Language");
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    // the newest gesture has no german content, it is served in french
+    assert_eq!(gestures[0].meanings.len(), 2);
+    assert_eq!(gestures[0].pictures.len(), 2);
+    assert_eq!(gestures[1].meanings.len(), 1);
+    assert_eq!(gestures[1].meanings[0].value, "Besser spät als nie");
+    assert!(gestures[1].descriptions.is_empty());
+    assert!(gestures[1].pictures.is_empty());
+
+    // the lang param wins over the header
+    let res = client
+        .get(&format!("{}/gestures?lang=fr&negotiate=true", address))
+        .header("Accept-Language", "de")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["content-language"], "fr");
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures[1].meanings.len(), 1);
+    assert_eq!(gestures[1].meanings[0].value, "Mieux vaut tard que jamais");
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_unknown_accept_language_should_fall_back() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    insert_german_meaning();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?negotiate=true", address))
+        .header("Accept-Language", "es")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["content-language"], "fr");
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures[1].meanings.len(), 1);
+    assert_eq!(gestures[1].meanings[0].value, "Mieux vaut tard que jamais");
+
+    // without header nor lang, everything is served
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.headers().get("content-language").is_none());
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures[1].meanings.len(), 2);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_from_a_browser_should_keep_every_lang() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    insert_german_meaning();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?max=10&page=1", address))
+        .header("Accept-Language", "en-US,en;q=0.9,fr;q=0.8")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.headers().get("content-language").is_none());
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures[0].meanings.len(), 2);
+    assert_eq!(gestures[0].pictures.len(), 2);
+    assert_eq!(gestures[1].meanings.len(), 2);
+    assert_eq!(gestures[1].descriptions.len(), 1);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_created_after_should_return_newer_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!(
+            "{}/gestures?created_after=2000-01-01T00:00:00",
+            address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 2);
+
+    let res = client
+        .get(&format!(
+            "{}/gestures?created_after=3000-01-01T00:00:00",
+            address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures, vec![]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_facets_should_count_tags_and_langs_of_all_pages() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?facets=true&max=1", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let page: GesturesPage = res.json().await.unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.total, 2);
+    let count = |value: &str, count| FacetCount {
+        value: value.to_owned(),
+        count,
+    };
+    assert_eq!(
+        page.facets,
+        Some(Facets {
+            tags: vec![count("tag1", 2), count("bras", 1), count("jambe", 1)],
+            langs: vec![count("fr", 2), count("us", 2)],
+        })
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_facets_should_count_only_matching_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?facets=true&search=bras", address))
+        .send()
+        .await
+        .unwrap();
+
+    let page: GesturesPage = res.json().await.unwrap();
+    assert_eq!(page.total, 1);
+    let tags: Vec<String> = page
+        .facets
+        .unwrap()
+        .tags
+        .into_iter()
+        .map(|t| t.value)
+        .collect();
+    assert_eq!(tags, vec!["bras", "tag1"]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_highlight_matched_text() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?search=papillon", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].matched_in, Some(vec!["description".to_owned()]));
+    assert_eq!(
+        gestures[0].snippet,
+        Some("Minute <mark>papillon</mark>".to_owned())
+    );
+
+    let res = client
+        .get(&format!("{}/gestures?search=bras", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures[0].matched_in, Some(vec!["tag".to_owned()]));
+    assert_eq!(gestures[0].snippet, None);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_search_should_highlight_accented_words_in_escaped_text() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/descriptions",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewDescription {
+            value: "Se frotter l'épaule <vite> & fort".to_owned(),
+            langs: vec!["fr".to_owned()],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    for search in &["epaule", "épaule"] {
+        let res = client
+            .get(&format!("{}/gestures", address))
+            .query(&[("search", search)])
+            .send()
+            .await
+            .unwrap();
+        let gestures: Vec<Gesture> = res.json().await.unwrap();
+        assert_eq!(gestures.len(), 1);
+        let snippet = gestures[0].snippet.clone().unwrap();
+        assert!(snippet.contains("<mark>épaule</mark>"), "{}", snippet);
+        assert!(snippet.contains("&lt;vite&gt; &amp; fort"), "{}", snippet);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_without_search_should_not_return_matches() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert!(body[0].get("matched_in").is_none());
+    assert!(body[0].get("snippet").is_none());
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_cursor_should_follow_next_cursor() {
+    setup::reset_db();
+    setup::insert_2_gestures_with_full_links();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?max=1&cursor=", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let page: GesturesPage = res.json().await.unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].id, "ce27c124-e47b-490f-b8fe-3f37d5dbbef6");
+    let next_cursor = page.next_cursor.unwrap();
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .query(&[("max", "1"), ("cursor", next_cursor.as_str())])
+        .send()
+        .await
+        .unwrap();
+    let page: GesturesPage = res.json().await.unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].id, "16991982-1752-4aa0-bb22-db3fbceb3780");
+    assert_eq!(page.next_cursor, None);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_should_reject_invalid_pagination() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+
+    for query in &[
+        "page=0",
+        "max=0",
+        "max=1000",
+        "cursor=not-a-cursor",
+        "cursor=&search=bras",
+    ] {
+        let res = client
+            .get(&format!("{}/gestures?{}", address, query))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_should_count_more_than_u16_gestures() {
+    setup::reset_db();
+    // triggers are only needed by search, skipping them keeps the insert fast
+    setup::connect()
+        .batch_execute(
+            "ALTER TABLE gestures DISABLE TRIGGER USER;
+            INSERT INTO gestures(id_gesture, tags)
+                SELECT md5(i::text)::uuid, '{}' FROM generate_series(1, 70000) AS i;
+            ALTER TABLE gestures ENABLE TRIGGER USER;",
+        )
+        .unwrap();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures?max=1&cursor=", address))
+        .send()
+        .await
+        .unwrap();
+    let page: GesturesPage = res.json().await.unwrap();
+    assert_eq!(page.total, 70000);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_sort_should_order_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    // a later modification brings the oldest gesture first when sorting by update
+    setup::connect()
+        .execute(
+            "UPDATE gestures SET tags = '{\"tag1\", \"jambe\", \"pied\"}' WHERE id_gesture = '16991982-1752-4aa0-bb22-db3fbceb3780'",
+            &[],
+        )
+        .unwrap();
+    setup::connect()
+        .execute(
+            "INSERT INTO meanings(id_meaning, id_gesture, val, langs)
+            VALUES ('3b4d1a6c-3f5e-4c9a-9b0e-6c1d2e3f4a5b', '16991982-1752-4aa0-bb22-db3fbceb3780', 'À bientôt', '{\"de\"}')",
+            &[],
+        )
+        .unwrap();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    for (query, expected) in &[
+        (
+            "sort=newest",
+            [
+                "ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+                "16991982-1752-4aa0-bb22-db3fbceb3780",
+            ],
+        ),
+        (
+            "sort=oldest",
+            [
+                "16991982-1752-4aa0-bb22-db3fbceb3780",
+                "ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+            ],
+        ),
+        (
+            "sort=updated",
+            [
+                "16991982-1752-4aa0-bb22-db3fbceb3780",
+                "ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+            ],
+        ),
+        (
+            "sort=meaning",
+            [
+                "16991982-1752-4aa0-bb22-db3fbceb3780",
+                "ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+            ],
+        ),
+        (
+            "sort=meaning&lang=fr",
+            [
+                "ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+                "16991982-1752-4aa0-bb22-db3fbceb3780",
+            ],
+        ),
+    ] {
+        let res = client
+            .get(&format!("{}/gestures?{}", address, query))
+            .send()
+            .await
+            .unwrap();
+        let gestures: Vec<Gesture> = res.json().await.unwrap();
+        let ids: Vec<&str> = gestures.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(&ids, expected, "{}", query);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_with_random_sort_should_keep_order_of_a_seed() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let mut orders = Vec::new();
+    for _ in 0..2 {
+        let res = client
+            .get(&format!("{}/gestures?sort=random&seed=discover", address))
+            .send()
+            .await
+            .unwrap();
+        let gestures: Vec<Gesture> = res.json().await.unwrap();
+        assert_eq!(gestures.len(), 2);
+        orders.push(gestures.into_iter().map(|g| g.id).collect::<Vec<_>>());
+    }
+    assert_eq!(orders[0], orders[1]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_should_reject_invalid_sort() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+
+    for query in &["sort=creation_date DESC", "sort=oldest&cursor="] {
+        let res = client
+            .get(&format!("{}/gestures?{}", address, query))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_random_gesture_should_pick_a_matching_gesture() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures/random?tags=jambe&lang=fr", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let gesture: Gesture = res.json().await.unwrap();
+    assert_eq!(gesture.id, "16991982-1752-4aa0-bb22-db3fbceb3780");
+    assert!(!gesture.descriptions.is_empty());
+
+    let res = client
+        .get(&format!("{}/gestures/random?tags=unknown", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_daily_gesture_should_pick_the_same_gesture() {
+    setup::reset_db();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(&format!("{}/gestures/daily", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    setup::insert_2_gestures_some_content();
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let res = client
+            .get(&format!("{}/gestures/daily", address))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let gesture: Gesture = res.json().await.unwrap();
+        ids.push(gesture.id);
+    }
+    assert_eq!(ids[0], ids[1]);
+    assert_eq!(ids[1], ids[2]);
+}
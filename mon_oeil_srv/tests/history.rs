@@ -0,0 +1,226 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_auth::Credentials;
+use mon_oeil_core::*;
+use utils::setup;
+
+#[actix_rt::test]
+#[serial]
+async fn get_history_should_reject_unauth() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/history",
+            address
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_history_after_put_description_should_return_before_and_after() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let new_description = NewDescription {
+        value: "Une autre description".to_owned(),
+        langs: vec!["fr".to_owned()],
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .json(&new_description)
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .get(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/history",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let revisions: Vec<Revision> = res.json().await.unwrap();
+
+    // gesture insert, description insert and description update
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[0].entity, "descriptions");
+    assert_eq!(
+        revisions[0].id_entity,
+        "2ae70884-97bd-401d-8f43-d1778d4502d2"
+    );
+    assert_eq!(revisions[0].action, "update");
+    assert_eq!(
+        revisions[0].before.as_ref().unwrap()["val"],
+        "Une petite description"
+    );
+    assert_eq!(
+        revisions[0].after.as_ref().unwrap()["val"],
+        "Une autre description"
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn history_should_record_logged_user() {
+    setup::reset_db();
+    setup::insert_user();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let credential = Credentials {
+        username: "user_test".to_owned(),
+        password: "password_test".to_owned(),
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/login", address))
+        .json(&credential)
+        .send()
+        .await
+        .unwrap();
+    let jwt = res.text().await.unwrap();
+    let jwt = jwt.replace("\"", "");
+
+    let updatable_gesture = NewGesture {
+        tags: vec!["tag3".to_owned()],
+    };
+    let res = client
+        .put(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6",
+            address
+        ))
+        .header("Authorization", format!("Bearer {}", jwt))
+        .header("If-Match", "\"1\"")
+        .json(&updatable_gesture)
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .get(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/history",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    let revisions: Vec<Revision> = res.json().await.unwrap();
+    assert_eq!(revisions[0].action, "update");
+    assert_eq!(revisions[0].username, Some("user_test".to_owned()));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn revert_should_restore_a_deleted_description() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .delete(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .get(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/history",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    let revisions: Vec<Revision> = res.json().await.unwrap();
+    let insert = revisions
+        .iter()
+        .find(|revision| revision.entity == "descriptions" && revision.action == "insert")
+        .unwrap();
+
+    let res = client
+        .post(&format!("{}/history/{}/revert", address, insert.id))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+
+    assert_eq!(
+        gestures[0].descriptions,
+        vec![Description {
+            id: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+            version: 2,
+            value: "Une petite description".to_owned(),
+            langs: vec!["fr".to_owned(), "us".to_owned()],
+            meanings: vec![],
+            translation_group: None,
+            source: None,
+        }]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn revert_not_existing_revision_should_fail() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/history/42/revert", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
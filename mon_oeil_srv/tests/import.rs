@@ -0,0 +1,384 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use mon_oeil_storage::*;
+use reqwest::multipart;
+use utils::setup;
+
+const JSON_IMPORT: &str = r#"[
+    {
+        "tags": ["insulte", "main"],
+        "descriptions": [
+            {
+                "value": "Le majeur levé",
+                "langs": ["fr"],
+                "meanings": [{ "value": "Va te faire voir", "langs": ["fr"] }]
+            }
+        ],
+        "meanings": [{ "value": "Une insulte", "langs": ["fr", "en"] }],
+        "pictures": [{ "path": "pictures/majeur.png", "langs": ["fr"] }]
+    },
+    {
+        "tags": ["salut"],
+        "meanings": [{ "value": "Bonjour", "langs": ["fr"] }]
+    }
+]"#;
+
+fn import_form(file_name: &'static str, content: &str) -> multipart::Form {
+    multipart::Form::new().part(
+        "file",
+        multipart::Part::text(content.to_owned()).file_name(file_name),
+    )
+}
+
+fn count_gestures() -> i64 {
+    setup::connect()
+        .query_one("SELECT COUNT(*) FROM gestures", &[])
+        .unwrap()
+        .get(0)
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_reject_unauth() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(import_form("book.json", JSON_IMPORT))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_add_gestures_and_upload_pictures() {
+    setup::reset_db();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        storage.expect_upload().times(1).returning(|_, _, _| Ok(()));
+        storage
+            .expect_get_url()
+            .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+        storage
+    });
+
+    let file = std::fs::read("asset/dummy.png").unwrap();
+    let form = import_form("book.json", JSON_IMPORT).part(
+        "pictures/majeur.png",
+        multipart::Part::bytes(file).file_name("majeur.png"),
+    );
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(form)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let report: ImportReport = res.json().await.unwrap();
+    assert_eq!(report.gestures, 2);
+    assert_eq!(report.descriptions, 1);
+    assert_eq!(report.meanings, 3);
+    assert_eq!(report.pictures, 1);
+    assert_eq!(report.ids.len(), 2);
+    assert!(report.errors.is_empty());
+
+    let res = client
+        .get(&format!("{}/gestures?tags=insulte", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+    assert_eq!(gestures[0].id, report.ids[0]);
+    assert_eq!(gestures[0].descriptions[0].value, "Le majeur levé");
+    assert_eq!(
+        gestures[0].descriptions[0].meanings[0].value,
+        "Va te faire voir"
+    );
+    assert_eq!(gestures[0].meanings[0].langs, vec!["fr", "en"]);
+    assert!(gestures[0].pictures[0].url.ends_with(".png"));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_keep_the_order_of_the_file() {
+    setup::reset_db();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let content = r#"[
+        {
+            "tags": ["un"],
+            "meanings": [
+                { "value": "Premier", "langs": ["fr"] },
+                { "value": "Deuxième", "langs": ["fr"] },
+                { "value": "Troisième", "langs": ["fr"] }
+            ]
+        },
+        { "tags": ["deux"] },
+        { "tags": ["trois"] }
+    ]"#;
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(import_form("book.json", content))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let report: ImportReport = res.json().await.unwrap();
+
+    let res = client
+        .get(&format!("{}/gestures?sort=oldest", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    let ids: Vec<String> = gestures.iter().map(|g| g.id.clone()).collect();
+    assert_eq!(ids, report.ids);
+    let meanings: Vec<&str> = gestures[0]
+        .meanings
+        .iter()
+        .map(|m| m.value.as_str())
+        .collect();
+    assert_eq!(meanings, vec!["Premier", "Deuxième", "Troisième"]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_with_dry_run_should_add_nothing() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let yaml = "
+- tags: [salut]
+  descriptions:
+    - value: La main levée
+      langs: [fr]
+";
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import?dry_run=true", address))
+        .multipart(import_form("book.yaml", yaml))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let report: ImportReport = res.json().await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.gestures, 1);
+    assert_eq!(report.descriptions, 1);
+    assert!(report.ids.is_empty());
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_report_errors_by_line() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let csv = "gesture,description,kind,value,langs,path
+p12,,gesture,insulte;main,,
+p12,d1,description,\"Le majeur levé, les autres doigts repliés\",fr,
+p12,d1,meaning,,fr,
+p13,,meaning,Bonjour,fr,
+p12,,picture,,fr,pictures/majeur.gif
+";
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import?format=csv", address))
+        .multipart(import_form("book.txt", csv))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let report: ImportReport = res.json().await.unwrap();
+    let locations: Vec<&str> = report
+        .errors
+        .iter()
+        .map(|error| error.location.as_str())
+        .collect();
+    assert_eq!(locations, vec!["line 4", "line 5", "line 6"]);
+    assert_eq!(report.errors[0].message, "value is empty");
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_report_errors_of_yaml_entries_by_line() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let yaml = "- tags: [salut]
+  descriptions:
+    - value: ''
+      langs: [fr]
+  meanings:
+    - value: Bonjour
+      langs: []
+";
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(import_form("book.yaml", yaml))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let report: ImportReport = res.json().await.unwrap();
+    assert_eq!(
+        report.errors,
+        vec![
+            ImportError {
+                location: "line 3, gestures[0].descriptions[0]".to_owned(),
+                message: "value is empty".to_owned(),
+            },
+            ImportError {
+                location: "line 6, gestures[0].meanings[0]".to_owned(),
+                message: "langs are missing".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_delete_uploaded_pictures_when_an_upload_fails() {
+    setup::reset_db();
+
+    let address = setup::spawn_app_with_storage(|| {
+        let mut storage = Storage::default();
+        let mut uploads = 0;
+        storage.expect_upload().times(2).returning(move |_, _, _| {
+            uploads += 1;
+            if uploads == 1 {
+                Ok(())
+            } else {
+                Err(StorageError::Other("bucket unavailable".to_owned()))
+            }
+        });
+        storage.expect_delete().times(1).returning(|_, _| Ok(()));
+
+        storage
+    });
+
+    let json = r#"[
+        {
+            "tags": ["salut"],
+            "pictures": [
+                { "path": "pictures/main.png", "langs": ["fr"] },
+                { "path": "pictures/bras.png", "langs": ["fr"] }
+            ]
+        }
+    ]"#;
+    let file = std::fs::read("asset/dummy.png").unwrap();
+    let form = import_form("book.json", json)
+        .part(
+            "pictures/main.png",
+            multipart::Part::bytes(file.clone()).file_name("main.png"),
+        )
+        .part(
+            "pictures/bras.png",
+            multipart::Part::bytes(file).file_name("bras.png"),
+        );
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(form)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_report_missing_picture_files() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(import_form("book.json", JSON_IMPORT))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let report: ImportReport = res.json().await.unwrap();
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].location, "pictures/majeur.png");
+    assert_eq!(count_gestures(), 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_import_should_report_unknown_langs() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let json = r#"[
+        {
+            "tags": ["salut"],
+            "descriptions": [{ "value": "La main levée", "langs": ["FR"] }],
+            "meanings": [{ "value": "Hello", "langs": ["en", "us"] }]
+        }
+    ]"#;
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/import", address))
+        .multipart(import_form("book.json", json))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let report: ImportReport = res.json().await.unwrap();
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].location, "line 5, gestures[0].meanings[0]");
+    assert_eq!(report.errors[0].message, "unknown langs \"us\"");
+    assert_eq!(count_gestures(), 0);
+}
@@ -0,0 +1,251 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+#[actix_rt::test]
+#[serial]
+async fn get_languages_should_list_known_languages() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/languages", address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let languages: Vec<Language> = res.json().await.unwrap();
+    assert!(languages.contains(&Language {
+        code: "fr".to_owned(),
+        name: "Français".to_owned(),
+    }));
+    assert!(languages.iter().any(|language| language.code == "en-US"));
+    assert!(!languages.iter().any(|language| language.code == "us"));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_language_should_reject_unauth() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/languages", address))
+        .json(&Language {
+            code: "pt-BR".to_owned(),
+            name: "Português (Brasil)".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_language_should_store_code_in_bcp47_case() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/languages", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&Language {
+            code: "pt-br".to_owned(),
+            name: "Português (Brasil)".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.text().await.unwrap(), "pt-BR");
+
+    // langs of content take the case of the table too
+    let res = client
+        .post(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/descriptions",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewDescription {
+            value: "Uma pequena descrição".to_owned(),
+            langs: vec!["PT-br".to_owned()],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let langs: Vec<String> = setup::connect()
+        .query_one("SELECT langs FROM descriptions", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(langs, vec!["pt-BR"]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_language_should_reject_invalid_or_existing_code() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    for (code, status) in &[
+        ("fr ", StatusCode::BAD_REQUEST),
+        ("french", StatusCode::BAD_REQUEST),
+        ("en-", StatusCode::BAD_REQUEST),
+        ("FR", StatusCode::CONFLICT),
+    ] {
+        let res = client
+            .post(&format!("{}/languages", address))
+            .header("Authorization", setup::ADMIN_TOKEN)
+            .json(&Language {
+                code: (*code).to_owned(),
+                name: "Français".to_owned(),
+            })
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), *status, "{:?}", code);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_description_with_unknown_lang_should_be_rejected() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!(
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/descriptions",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewDescription {
+            value: "Une petite description".to_owned(),
+            langs: vec!["fr ".to_owned(), "us".to_owned()],
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(
+        body["message"],
+        "Unknown langs \"fr \", \"us\", see /languages"
+    );
+    let count: i64 = setup::connect()
+        .query_one("SELECT COUNT(*) FROM descriptions", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_and_delete_language_should_manage_the_list() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/languages/en", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&LanguageName {
+            name: "Anglais".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!("{}/languages/ru", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!("{}/languages/ru", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let res = client
+        .get(&format!("{}/languages", address))
+        .send()
+        .await
+        .unwrap();
+    let languages: Vec<Language> = res.json().await.unwrap();
+    assert!(languages.contains(&Language {
+        code: "en".to_owned(),
+        name: "Anglais".to_owned(),
+    }));
+    assert!(!languages.iter().any(|language| language.code == "ru"));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_and_delete_language_should_ignore_code_case() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/languages/en-us", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&LanguageName {
+            name: "Américain".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!("{}/languages/EN-gb", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .get(&format!("{}/languages", address))
+        .send()
+        .await
+        .unwrap();
+    let languages: Vec<Language> = res.json().await.unwrap();
+    assert!(languages.contains(&Language {
+        code: "en-US".to_owned(),
+        name: "Américain".to_owned(),
+    }));
+    assert!(!languages.iter().any(|language| language.code == "en-GB"));
+}
@@ -18,7 +18,9 @@ async fn post_description_meaning_should_reject_unauth() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -45,7 +47,9 @@ async fn post_gesture_meaning_should_reject_unauth() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -72,7 +76,9 @@ async fn post_description_meaning_should_accept_auth() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -99,7 +105,9 @@ async fn post_gestures_meaning_should_accept_auth() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -126,7 +134,9 @@ async fn post_desctipions_meaning_on_non_existing_gesture_should_fail() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -153,7 +163,9 @@ async fn post_gesture_meaning_on_non_existing_gesture_should_fail() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -181,7 +193,9 @@ async fn post_description_meaning_should_return_new_uuid() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -214,7 +228,9 @@ async fn post_gesture_meaning_should_return_new_uuid() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -249,7 +265,9 @@ async fn post_decription_meaning_should_return_uuid_random() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -296,7 +314,9 @@ async fn post_gesture_meaning_should_return_uuid_random() {
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -344,7 +364,9 @@ async fn get_gestures_after_post_description_meaning_should_return_gesture_with_
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -375,19 +397,30 @@ async fn get_gestures_after_post_description_meaning_should_return_gesture_with_
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![Description {
                 id: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+                version: 1,
                 value: "Une petite description".to_owned(),
                 langs: vec!["fr".to_owned(), "us".to_owned()],
                 meanings: vec![Meaning {
                     id: uuid,
+                    version: 1,
                     value: "Un petit meaning".to_owned(),
-                    langs: vec!["fr".to_owned(), "us".to_owned()],
-                }]
+                    langs: vec!["fr".to_owned(), "en".to_owned()],
+                    translation_group: None,
+                    regions: vec![],
+                    register: None,
+                    source: None,
+                }],
+                translation_group: None,
+                source: None,
             }],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -403,7 +436,9 @@ async fn get_gestures_after_post_gestures_meaning_should_return_gesture_with_pos
 
     let new_meaning = NewMeaning {
         value: "Un petit meaning".to_owned(),
-        langs: vec!["fr".to_owned(), "us".to_owned()],
+        langs: vec!["fr".to_owned(), "en".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -433,14 +468,22 @@ async fn get_gestures_after_post_gestures_meaning_should_return_gesture_with_pos
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![],
             meanings: vec![Meaning {
                 id: uuid,
+                version: 1,
                 value: "Un petit meaning".to_owned(),
-                langs: vec!["fr".to_owned(), "us".to_owned()],
+                langs: vec!["fr".to_owned(), "en".to_owned()],
+                translation_group: None,
+                regions: vec![],
+                register: None,
+                source: None,
             }],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -481,6 +524,7 @@ async fn delete_meaning_should_accept_auth() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -501,6 +545,7 @@ async fn delete_not_existing_meaning_should_fail() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -523,6 +568,7 @@ async fn get_gestures_after_delete_description_meaning_should_return_empty_descr
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -540,15 +586,21 @@ async fn get_gestures_after_delete_description_meaning_should_return_empty_descr
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![Description {
                 id: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+                version: 1,
                 value: "Une petite description".to_owned(),
                 langs: vec!["fr".to_owned(), "us".to_owned()],
-                meanings: vec![]
+                meanings: vec![],
+                translation_group: None,
+                source: None,
             }],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -570,6 +622,7 @@ async fn get_gestures_after_delete_gesture_meaning_should_return_empty_gesture()
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -587,10 +640,13 @@ async fn get_gestures_after_delete_gesture_meaning_should_return_empty_gesture()
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -606,7 +662,9 @@ async fn put_meaning_should_reject_unauth() {
 
     let new_meaning = NewMeaning {
         value: "Un autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -633,7 +691,9 @@ async fn put_meaning_should_accept_auth() {
 
     let new_meaning = NewMeaning {
         value: "Un autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -644,6 +704,7 @@ async fn put_meaning_should_accept_auth() {
         ))
         .json(&new_meaning)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -660,7 +721,9 @@ async fn put_not_existing_meaning_should_fail() {
 
     let new_meaning = NewMeaning {
         value: "Un autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -671,6 +734,7 @@ async fn put_not_existing_meaning_should_fail() {
         ))
         .json(&new_meaning)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -688,7 +752,9 @@ async fn get_gesture_after_put_meaning_should_return_gesture_with_updated_meanin
 
     let new_meaning = NewMeaning {
         value: "Un autre".to_owned(),
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
+        regions: vec![],
+        register: None,
     };
 
     let client = reqwest::Client::new();
@@ -699,6 +765,7 @@ async fn get_gesture_after_put_meaning_should_return_gesture_with_updated_meanin
         ))
         .json(&new_meaning)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -716,15 +783,195 @@ async fn get_gesture_after_put_meaning_should_return_gesture_with_updated_meanin
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![],
             meanings: vec![Meaning {
                 id: "59c25147-021e-4584-9c35-97cbf060cc89".to_owned(),
+                version: 2,
                 value: "Un autre".to_owned(),
-                langs: vec!["kr".to_owned()],
+                langs: vec!["de".to_owned()],
+                translation_group: None,
+                regions: vec![],
+                register: None,
+                source: None,
             }],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
 }
+
+#[actix_rt::test]
+#[serial]
+async fn put_meaning_should_set_regions_and_register() {
+    setup::reset_db();
+    setup::insert_gesture_with_meaning();
+
+    let address = setup::spawn_app();
+
+    let new_meaning = NewMeaning {
+        value: "Va te faire voir".to_owned(),
+        langs: vec!["fr".to_owned()],
+        regions: vec!["it".to_owned(), " FR".to_owned(), "IT".to_owned()],
+        register: Some("offensive".to_owned()),
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/meanings/59c25147-021e-4584-9c35-97cbf060cc89",
+            address
+        ))
+        .json(&new_meaning)
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+
+    assert_eq!(
+        gestures[0].meanings,
+        vec![Meaning {
+            id: "59c25147-021e-4584-9c35-97cbf060cc89".to_owned(),
+            version: 2,
+            value: "Va te faire voir".to_owned(),
+            langs: vec!["fr".to_owned()],
+            translation_group: None,
+            regions: vec!["IT".to_owned(), "FR".to_owned()],
+            register: Some("offensive".to_owned()),
+            source: None,
+        }]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_meaning_should_reject_invalid_region_or_register() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    for (regions, register) in vec![
+        (vec!["ITA".to_owned()], None),
+        (vec![], Some("rude".to_owned())),
+    ] {
+        let res = client
+            .post(&format!(
+                "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/meanings",
+                address
+            ))
+            .json(&NewMeaning {
+                value: "Un petit meaning".to_owned(),
+                langs: vec!["fr".to_owned()],
+                regions,
+                register,
+            })
+            .header("Authorization", setup::ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_gestures_should_filter_by_meaning_region_and_register() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    setup::connect()
+        .batch_execute(
+            "UPDATE meanings SET regions = '{IT}', register = 'vulgar'
+                WHERE id_meaning = 'e2c6eee0-49a7-49c4-9a0f-a9c6e6f668d8';
+            UPDATE meanings SET regions = '{IT, FR}', register = 'neutral'
+                WHERE id_meaning = '4719b1d7-2810-4f7d-865d-03ee44cf0add';
+            UPDATE meanings SET register = 'familiar'
+                WHERE id_meaning = '59c25147-021e-4584-9c35-97cbf060cc89';",
+        )
+        .unwrap();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let ids = |gestures: Vec<Gesture>| {
+        let mut ids: Vec<String> = gestures.into_iter().map(|gesture| gesture.id).collect();
+        ids.sort();
+        ids
+    };
+
+    let res = client
+        .get(&format!("{}/gestures?region=it", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        ids(res.json().await.unwrap()),
+        vec![
+            "16991982-1752-4aa0-bb22-db3fbceb3780".to_owned(),
+            "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned()
+        ]
+    );
+
+    let res = client
+        .get(&format!("{}/gestures?region=FR&register=vulgar", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(ids(res.json().await.unwrap()).is_empty());
+
+    // a meaning without regions holds everywhere
+    let res = client
+        .get(&format!("{}/gestures?region=DE&register=familiar", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(
+        ids(gestures.clone()),
+        vec!["ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned()]
+    );
+    // meanings are not narrowed to the region
+    assert_eq!(gestures[0].meanings.len(), 2);
+
+    let res = client
+        .get(&format!("{}/gestures?region=DE&register=neutral", address))
+        .send()
+        .await
+        .unwrap();
+    assert!(ids(res.json().await.unwrap()).is_empty());
+
+    let res = client
+        .get(&format!("{}/gestures?register=vulgar", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        ids(res.json().await.unwrap()),
+        vec!["ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned()]
+    );
+
+    let res = client
+        .get(&format!("{}/gestures?region=Italy", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
@@ -33,7 +33,7 @@ async fn post_picture_should_reject_unauth() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -66,7 +66,7 @@ async fn post_picture_should_accept_auth() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -91,7 +91,7 @@ async fn post_picture_should_reject_no_file() {
     let client = reqwest::Client::new();
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
@@ -124,7 +124,7 @@ async fn post_picture_on_non_existing_gesture_should_fail() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -243,7 +243,7 @@ async fn post_picture_accept_png() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -280,7 +280,7 @@ async fn post_picture_should_accept_jpg() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -317,7 +317,7 @@ async fn post_picture_should_reject_txt() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -354,7 +354,7 @@ async fn post_picture_should_return_new_uuid() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -398,7 +398,7 @@ async fn post_gestures_should_return_uuid_random() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -421,7 +421,7 @@ async fn post_gestures_should_return_uuid_random() {
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -464,7 +464,7 @@ async fn get_gestures_after_post_picture_should_return_gesture_with_posted_pictu
     );
     let res = client
         .post(&format!(
-            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;us",
+            "{}/gestures/ce27c124-e47b-490f-b8fe-3f37d5dbbef6/pictures?langs=fr;en",
             address
         ))
         .multipart(form)
@@ -489,14 +489,21 @@ async fn get_gestures_after_post_picture_should_return_gesture_with_posted_pictu
         gestures,
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags: vec!["tag1".to_string(), "tag2".to_string()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![],
             meanings: vec![],
             pictures: vec![Picture {
                 id: uuid.clone(),
-                langs: vec!["fr".to_owned(), "us".to_owned()],
+                version: 1,
+                langs: vec!["fr".to_owned(), "en".to_owned()],
                 url: format!("http://monoielfakeapp.com/{}.png", uuid),
-            }]
+                source: None,
+            }],
+            categories: vec![],
+            related: vec![],
         }]
     )
 }
@@ -541,6 +548,7 @@ async fn delete_picture_should_accept_auth() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -566,6 +574,7 @@ async fn delete_not_existing_picture_should_fail() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -593,6 +602,7 @@ async fn get_gestures_after_delete_picture_should_return_nothing() {
             address
         ))
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -610,10 +620,13 @@ async fn get_gestures_after_delete_picture_should_return_nothing() {
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags,
             descriptions: vec![],
             meanings: vec![],
-            pictures: vec![]
+            pictures: vec![],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -628,7 +641,7 @@ async fn put_picture_meta_should_reject_unauth() {
     let address = setup::spawn_app();
 
     let new_picture_meta = NewPictureMeta {
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -654,7 +667,7 @@ async fn put_picture_meta_should_accept_auth() {
     let address = setup::spawn_app();
 
     let new_picture_meta = NewPictureMeta {
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -665,6 +678,7 @@ async fn put_picture_meta_should_accept_auth() {
         ))
         .json(&new_picture_meta)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -680,7 +694,7 @@ async fn put_picture_meta_on_not_existing_picture_should_fail() {
     let address = setup::spawn_app();
 
     let new_picture_meta = NewPictureMeta {
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -691,6 +705,7 @@ async fn put_picture_meta_on_not_existing_picture_should_fail() {
         ))
         .json(&new_picture_meta)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -714,7 +729,7 @@ async fn get_gesture_after_put_picture_meta_should_return_gesture_with_updated_p
     });
 
     let new_picture_meta = NewPictureMeta {
-        langs: vec!["kr".to_owned()],
+        langs: vec!["de".to_owned()],
     };
 
     let client = reqwest::Client::new();
@@ -725,6 +740,7 @@ async fn get_gesture_after_put_picture_meta_should_return_gesture_with_updated_p
         ))
         .json(&new_picture_meta)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -742,15 +758,22 @@ async fn get_gesture_after_put_picture_meta_should_return_gesture_with_updated_p
     assert_eq!(
         vec![Gesture {
             id: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+            version: 1,
             tags: vec!["tag1".to_owned(), "tag2".to_owned()],
+            matched_in: None,
+            snippet: None,
             descriptions: vec![],
             meanings: vec![],
             pictures: vec![Picture {
                 id: "283e7b04-7c13-4154-aafe-8e55b6960fe3".to_owned(),
-                langs: vec!["kr".to_owned()],
+                version: 2,
+                langs: vec!["de".to_owned()],
                 url: "http://monoielfakeapp.com/283e7b04-7c13-4154-aafe-8e55b6960fe3.png"
                     .to_owned(),
-            }]
+                source: None,
+            }],
+            categories: vec![],
+            related: vec![],
         }],
         gestures
     )
@@ -817,6 +840,7 @@ async fn put_picture_file_should_accept_auth() {
         ))
         .multipart(form)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -848,6 +872,7 @@ async fn put_picture_file_on_not_existing_picture_should_fail() {
         ))
         .multipart(form)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
@@ -885,6 +910,7 @@ async fn put_picture_file_with_other_format_should_change_format() {
         ))
         .multipart(form)
         .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
         .send()
         .await
         .unwrap();
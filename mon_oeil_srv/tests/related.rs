@@ -0,0 +1,200 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+const G1: &str = "16991982-1752-4aa0-bb22-db3fbceb3780";
+const G2: &str = "ce27c124-e47b-490f-b8fe-3f37d5dbbef6";
+
+async fn put_relation(
+    client: &reqwest::Client,
+    address: &str,
+    id: &str,
+    id_related: &str,
+    kind: Relation,
+) -> StatusCode {
+    client
+        .put(&format!(
+            "{}/gestures/{}/related/{}",
+            address, id, id_related
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&GestureRelation { kind })
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn related_by_gesture(
+    client: &reqwest::Client,
+    address: &str,
+) -> Vec<(String, Vec<RelatedGesture>)> {
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    let mut gestures: Vec<(String, Vec<RelatedGesture>)> = gestures
+        .into_iter()
+        .map(|gesture| (gesture.id, gesture.related))
+        .collect();
+    gestures.sort_by(|a, b| a.0.cmp(&b.0));
+    gestures
+}
+
+fn related(id: &str, kind: &str) -> Vec<RelatedGesture> {
+    vec![RelatedGesture {
+        id: id.to_owned(),
+        kind: kind.to_owned(),
+    }]
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_related_gesture_should_link_both_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let status = put_relation(&client, &address, G1, G2, Relation::VariantOf).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let gestures = related_by_gesture(&client, &address).await;
+    assert_eq!(
+        gestures,
+        vec![
+            (G1.to_owned(), related(G2, "variant_of")),
+            (G2.to_owned(), related(G1, "has_variant")),
+        ]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_related_gesture_should_replace_previous_link() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let status = put_relation(&client, &address, G1, G2, Relation::SeeAlso).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let status = put_relation(&client, &address, G2, G1, Relation::HasVariant).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let gestures = related_by_gesture(&client, &address).await;
+    assert_eq!(
+        gestures,
+        vec![
+            (G1.to_owned(), related(G2, "variant_of")),
+            (G2.to_owned(), related(G1, "has_variant")),
+        ]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_related_gesture_should_reject_unauth() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/gestures/{}/related/{}", address, G1, G2))
+        .json(&GestureRelation {
+            kind: Relation::SeeAlso,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_related_gesture_should_reject_invalid_links() {
+    setup::reset_db();
+    setup::insert_gesture_without_links();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let status = put_relation(&client, &address, G2, G2, Relation::OppositeOf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let status = put_relation(&client, &address, G2, G1, Relation::OppositeOf).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let status = put_relation(&client, &address, G1, G2, Relation::OppositeOf).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_related_gesture_should_unlink_both_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let status = put_relation(&client, &address, G1, G2, Relation::OppositeOf).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!("{}/gestures/{}/related/{}", address, G2, G1))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let gestures = related_by_gesture(&client, &address).await;
+    assert!(gestures.iter().all(|(_, related)| related.is_empty()));
+
+    let res = client
+        .delete(&format!("{}/gestures/{}/related/{}", address, G2, G1))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_gesture_should_remove_its_links() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let status = put_relation(&client, &address, G1, G2, Relation::SeeAlso).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!("{}/gestures/{}", address, G1))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+
+    let gestures = related_by_gesture(&client, &address).await;
+    assert_eq!(gestures, vec![(G2.to_owned(), vec![])]);
+}
@@ -0,0 +1,32 @@
+#[macro_use]
+extern crate serial_test;
+
+mod utils;
+
+use mon_oeil_db::DbError;
+use utils::setup;
+
+#[actix_rt::test]
+#[serial]
+async fn check_schema_should_accept_schema_sql() {
+    setup::reset_db();
+
+    assert_eq!(Ok(()), setup::CONF.db_pool.check_schema().await);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn check_schema_should_report_missing_column() {
+    setup::reset_db();
+    setup::connect()
+        .execute("ALTER TABLE pictures DROP COLUMN format", &[])
+        .unwrap();
+
+    assert_eq!(
+        Err(DbError::Mapping {
+            column: "pictures.format".to_owned(),
+            cause: "missing column".to_owned()
+        }),
+        setup::CONF.db_pool.check_schema().await
+    );
+}
@@ -0,0 +1,220 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+fn book(page: i32) -> Source {
+    Source {
+        kind: "book".to_owned(),
+        page: Some(page),
+        reference: None,
+        attribution: None,
+        license: None,
+    }
+}
+
+async fn put_source(
+    client: &reqwest::Client,
+    address: &str,
+    path: &str,
+    source: &Source,
+) -> reqwest::Response {
+    client
+        .put(&format!("{}/{}/source", address, path))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"1\"")
+        .json(source)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn get_gestures(client: &reqwest::Client, address: &str) -> Vec<Gesture> {
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    res.json().await.unwrap()
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_description_source_should_show_it_in_gestures() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = put_source(
+        &client,
+        &address,
+        "descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2",
+        &book(42),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers()["etag"], "\"2\"");
+
+    let gestures = get_gestures(&client, &address).await;
+    assert_eq!(gestures[0].descriptions[0].source, Some(book(42)));
+    assert_eq!(gestures[0].descriptions[0].version, 2);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_meaning_source_should_trim_its_texts() {
+    setup::reset_db();
+    setup::insert_gesture_with_meaning();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = put_source(
+        &client,
+        &address,
+        "meanings/59c25147-021e-4584-9c35-97cbf060cc89",
+        &Source {
+            kind: "external".to_owned(),
+            page: None,
+            reference: Some(" https://example.org/gestes ".to_owned()),
+            attribution: Some("  ".to_owned()),
+            license: Some("CC BY-SA 4.0".to_owned()),
+        },
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let gestures = get_gestures(&client, &address).await;
+    assert_eq!(
+        gestures[0].meanings[0].source,
+        Some(Source {
+            kind: "external".to_owned(),
+            page: None,
+            reference: Some("https://example.org/gestes".to_owned()),
+            attribution: None,
+            license: Some("CC BY-SA 4.0".to_owned()),
+        })
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_picture_source_should_clear_it() {
+    setup::reset_db();
+    setup::insert_gesture_with_picture();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let contributor = Source {
+        kind: "contributor".to_owned(),
+        page: None,
+        reference: None,
+        attribution: Some("Jean Dupont".to_owned()),
+        license: Some("CC BY 4.0".to_owned()),
+    };
+    let res = put_source(
+        &client,
+        &address,
+        "pictures/283e7b04-7c13-4154-aafe-8e55b6960fe3",
+        &contributor,
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let gestures = get_gestures(&client, &address).await;
+    assert_eq!(gestures[0].pictures[0].source, Some(contributor));
+
+    let res = client
+        .delete(&format!(
+            "{}/pictures/283e7b04-7c13-4154-aafe-8e55b6960fe3/source",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"2\"")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers()["etag"], "\"3\"");
+
+    let gestures = get_gestures(&client, &address).await;
+    assert_eq!(gestures[0].pictures[0].source, None);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_source_should_reject_unauth() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2/source",
+            address
+        ))
+        .header("If-Match", "\"1\"")
+        .json(&book(42))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_source_should_reject_invalid_source() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let path = "descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2";
+    let mut source = book(42);
+    source.kind = "blog".to_owned();
+    let res = put_source(&client, &address, path, &source).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    source.kind = "contributor".to_owned();
+    let res = put_source(&client, &address, path, &source).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = put_source(&client, &address, path, &book(0)).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_source_should_fail_on_version_mismatch() {
+    setup::reset_db();
+    setup::insert_gesture_with_description();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/2ae70884-97bd-401d-8f43-d1778d4502d2/source",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .header("If-Match", "\"2\"")
+        .json(&book(42))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+}
@@ -0,0 +1,115 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+#[actix_rt::test]
+#[serial]
+async fn get_suggest_should_return_prefixed_tags_with_counts() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/suggest?q=ta", address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let suggestions: Vec<Suggestion> = res.json().await.unwrap();
+    assert_eq!(
+        suggestions,
+        vec![Suggestion {
+            kind: "tag".to_owned(),
+            value: "tag1".to_owned(),
+            count: 2,
+        }]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_suggest_should_return_meanings_ignoring_accents_and_case() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/suggest", address))
+        .query(&[("q", "JÉAN p")])
+        .send()
+        .await
+        .unwrap();
+
+    let suggestions: Vec<Suggestion> = res.json().await.unwrap();
+    assert_eq!(
+        suggestions,
+        vec![Suggestion {
+            kind: "meaning".to_owned(),
+            value: "Jean pierre est dehors".to_owned(),
+            count: 1,
+        }]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_suggest_should_follow_deleted_gestures() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+    setup::connect()
+        .execute(
+            "DELETE FROM gestures WHERE id_gesture = '16991982-1752-4aa0-bb22-db3fbceb3780'",
+            &[],
+        )
+        .unwrap();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/suggest?q=tag", address))
+        .send()
+        .await
+        .unwrap();
+    let suggestions: Vec<Suggestion> = res.json().await.unwrap();
+    assert_eq!(suggestions[0].count, 1);
+
+    let res = client
+        .get(&format!("{}/suggest?q=jambe", address))
+        .send()
+        .await
+        .unwrap();
+    let suggestions: Vec<Suggestion> = res.json().await.unwrap();
+    assert_eq!(suggestions, vec![]);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_suggest_with_empty_or_wildcard_input_should_return_nothing() {
+    setup::reset_db();
+    setup::insert_2_gestures_some_content();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    for q in &["", "  ", "%"] {
+        let res = client
+            .get(&format!("{}/suggest", address))
+            .query(&[("q", q)])
+            .send()
+            .await
+            .unwrap();
+        let suggestions: Vec<Suggestion> = res.json().await.unwrap();
+        assert_eq!(suggestions, vec![]);
+    }
+}
@@ -0,0 +1,221 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+fn insert_gestures_with_close_tags() {
+    let mut client = setup::connect();
+    client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('ce27c124-e47b-490f-b8fe-3f37d5dbbef6', '{"insulte", "main"}')"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('16991982-1752-4aa0-bb22-db3fbceb3780', '{"insultes", "bras", "insulte"}')"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('03b9bfc6-fa22-4ffb-9464-93c1be842ace', '{"injure"}')"#, &[]).unwrap();
+}
+
+fn select_tags(id_gesture: &str) -> Vec<String> {
+    setup::connect()
+        .query_one(
+            "SELECT tags FROM gestures WHERE id_gesture = $1::text::uuid",
+            &[&id_gesture],
+        )
+        .unwrap()
+        .get(0)
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_tags_should_count_gestures_most_used_first() {
+    setup::reset_db();
+    insert_gestures_with_close_tags();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(&format!("{}/tags", address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let tags: Vec<Tag> = res.json().await.unwrap();
+    let tag = |name: &str, count| Tag {
+        name: name.to_owned(),
+        count,
+    };
+    assert_eq!(
+        tags,
+        vec![
+            tag("insulte", 2),
+            tag("bras", 1),
+            tag("injure", 1),
+            tag("insultes", 1),
+            tag("main", 1),
+        ]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn post_gesture_should_normalize_tags() {
+    setup::reset_db();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/gestures", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&NewGesture {
+            tags: vec![
+                " Insulte".to_owned(),
+                "doigt  d'honneur".to_owned(),
+                "insulte".to_owned(),
+                "".to_owned(),
+            ],
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let id = res.text().await.unwrap();
+
+    assert_eq!(select_tags(&id), vec!["insulte", "doigt d'honneur"]);
+
+    // filters are normalized the same way
+    let res = client
+        .get(&format!("{}/gestures?tags=INSULTE", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    assert_eq!(gestures.len(), 1);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_tag_should_rename_it_on_every_gesture() {
+    setup::reset_db();
+    insert_gestures_with_close_tags();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/tags/insulte", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&TagName {
+            name: "Grossièreté".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    assert_eq!(
+        select_tags("ce27c124-e47b-490f-b8fe-3f37d5dbbef6"),
+        vec!["grossièreté", "main"]
+    );
+    assert_eq!(
+        select_tags("16991982-1752-4aa0-bb22-db3fbceb3780"),
+        vec!["insultes", "bras", "grossièreté"]
+    );
+    let version: i32 = setup::connect()
+        .query_one(
+            "SELECT version FROM gestures WHERE id_gesture = 'ce27c124-e47b-490f-b8fe-3f37d5dbbef6'",
+            &[],
+        )
+        .unwrap()
+        .get(0);
+    assert_eq!(version, 2);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_tag_should_reject_unauth_unknown_or_used_name() {
+    setup::reset_db();
+    insert_gestures_with_close_tags();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!("{}/tags/insulte", address))
+        .json(&TagName {
+            name: "grossièreté".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    for (tag, name, status) in &[
+        ("unknown", "grossièreté", StatusCode::NOT_FOUND),
+        ("insultes", "insulte", StatusCode::CONFLICT),
+        ("insulte", "  ", StatusCode::BAD_REQUEST),
+    ] {
+        let res = client
+            .put(&format!("{}/tags/{}", address, tag))
+            .header("Authorization", setup::ADMIN_TOKEN)
+            .json(&TagName {
+                name: (*name).to_owned(),
+            })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), *status, "{} {}", tag, name);
+    }
+
+    assert_eq!(
+        select_tags("16991982-1752-4aa0-bb22-db3fbceb3780"),
+        vec!["insultes", "bras", "insulte"]
+    );
+}
+
+#[actix_rt::test]
+#[serial]
+async fn merge_tags_should_keep_a_single_tag_per_gesture() {
+    setup::reset_db();
+    insert_gestures_with_close_tags();
+
+    let address = setup::spawn_app();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&format!("{}/tags/merge", address))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&TagsMerge {
+            tags: vec!["insultes".to_owned(), "Injure".to_owned()],
+            into: "insulte".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    assert_eq!(
+        select_tags("16991982-1752-4aa0-bb22-db3fbceb3780"),
+        vec!["insulte", "bras"]
+    );
+    assert_eq!(
+        select_tags("03b9bfc6-fa22-4ffb-9464-93c1be842ace"),
+        vec!["insulte"]
+    );
+
+    let res = client
+        .get(&format!("{}/tags", address))
+        .send()
+        .await
+        .unwrap();
+    let tags: Vec<Tag> = res.json().await.unwrap();
+    assert_eq!(
+        tags[0],
+        Tag {
+            name: "insulte".to_owned(),
+            count: 3,
+        }
+    );
+    assert_eq!(tags.len(), 3);
+}
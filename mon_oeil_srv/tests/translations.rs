@@ -0,0 +1,268 @@
+#[macro_use]
+extern crate serial_test;
+use actix_web::http::StatusCode;
+
+mod utils;
+
+use mon_oeil_core::*;
+use utils::setup;
+
+fn insert_gestures_in_french_and_english() {
+    let mut client = setup::connect();
+    client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('ce27c124-e47b-490f-b8fe-3f37d5dbbef6', '{"tag1"}')"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('16991982-1752-4aa0-bb22-db3fbceb3780', '{"tag1"}')"#, &[]).unwrap();
+
+    client.execute(r#"INSERT INTO descriptions(
+            id_description, id_gesture, val, langs)
+            VALUES ('2ae70884-97bd-401d-8f43-d1778d4502d2', 'ce27c124-e47b-490f-b8fe-3f37d5dbbef6', 'Une petite description', '{"fr"}');"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO descriptions(
+            id_description, id_gesture, val, langs)
+            VALUES ('1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28', 'ce27c124-e47b-490f-b8fe-3f37d5dbbef6', 'A small description', '{"en"}');"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO descriptions(
+            id_description, id_gesture, val, langs)
+            VALUES ('cdbcd8fb-3d6d-4f09-86ba-37a6ec1dd293', '16991982-1752-4aa0-bb22-db3fbceb3780', 'Bryan est dans la cuisine', '{"fr"}');"#, &[]).unwrap();
+
+    client.execute(r#"INSERT INTO meanings(
+            id_meaning, id_description, id_gesture, val, langs)
+            VALUES ('59c25147-021e-4584-9c35-97cbf060cc89', null, 'ce27c124-e47b-490f-b8fe-3f37d5dbbef6', 'Un petit meaning', '{"fr"}');"#, &[]).unwrap();
+    client.execute(r#"INSERT INTO meanings(
+            id_meaning, id_description, id_gesture, val, langs)
+            VALUES ('4719b1d7-2810-4f7d-865d-03ee44cf0add', null, '16991982-1752-4aa0-bb22-db3fbceb3780', 'Better late than never', '{"en"}');"#, &[]).unwrap();
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_description_translation_should_share_a_translation_group() {
+    setup::reset_db();
+    insert_gestures_in_french_and_english();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28/translation",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&TranslationLink {
+            translation_of: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .get(&format!("{}/gestures", address))
+        .send()
+        .await
+        .unwrap();
+    let gestures: Vec<Gesture> = res.json().await.unwrap();
+    let gesture = gestures
+        .iter()
+        .find(|gesture| gesture.id == "ce27c124-e47b-490f-b8fe-3f37d5dbbef6")
+        .unwrap();
+    let groups: Vec<Option<String>> = gesture
+        .descriptions
+        .iter()
+        .map(|description| description.translation_group.clone())
+        .collect();
+    assert_eq!(
+        groups,
+        vec![
+            Some("2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned()),
+            Some("2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned())
+        ]
+    );
+    assert_eq!(gesture.meanings[0].translation_group, None);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_translation_should_reject_unauth() {
+    setup::reset_db();
+    insert_gestures_in_french_and_english();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28/translation",
+            address
+        ))
+        .json(&TranslationLink {
+            translation_of: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn put_translation_should_reject_other_gesture_or_unknown_item() {
+    setup::reset_db();
+    insert_gestures_in_french_and_english();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    for (id, translation_of, status) in &[
+        (
+            "4719b1d7-2810-4f7d-865d-03ee44cf0add",
+            "59c25147-021e-4584-9c35-97cbf060cc89",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            "59c25147-021e-4584-9c35-97cbf060cc89",
+            "59c25147-021e-4584-9c35-97cbf060cc89",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            "59c25147-021e-4584-9c35-97cbf060cc89",
+            "2ae70884-97bd-401d-8f43-d1778d4502d2",
+            StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let res = client
+            .put(&format!("{}/meanings/{}/translation", address, id))
+            .header("Authorization", setup::ADMIN_TOKEN)
+            .json(&TranslationLink {
+                translation_of: (*translation_of).to_owned(),
+            })
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), *status, "{} {}", id, translation_of);
+    }
+
+    let count: i64 = setup::connect()
+        .query_one(
+            "SELECT COUNT(*) FROM meanings WHERE translation_group IS NOT NULL",
+            &[],
+        )
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn delete_translation_should_remove_the_group_left_alone() {
+    setup::reset_db();
+    insert_gestures_in_french_and_english();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28/translation",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&TranslationLink {
+            translation_of: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .delete(&format!(
+            "{}/descriptions/1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28/translation",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let count: i64 = setup::connect()
+        .query_one(
+            "SELECT COUNT(*) FROM descriptions WHERE translation_group IS NOT NULL",
+            &[],
+        )
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+}
+
+#[actix_rt::test]
+#[serial]
+async fn get_missing_translations_should_skip_translated_content() {
+    setup::reset_db();
+    insert_gestures_in_french_and_english();
+
+    let address = setup::spawn_app_with_fake_urls();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(&format!(
+            "{}/descriptions/1c53f9ad-98b4-444c-9ec9-e8f92f1e5d28/translation",
+            address
+        ))
+        .header("Authorization", setup::ADMIN_TOKEN)
+        .json(&TranslationLink {
+            translation_of: "2ae70884-97bd-401d-8f43-d1778d4502d2".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .get(&format!("{}/translations/missing?lang=EN", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let missing: MissingTranslations = res.json().await.unwrap();
+    assert_eq!(missing.total, 2);
+    // content of the newest gesture first
+    assert_eq!(
+        missing.items,
+        vec![
+            MissingTranslation {
+                kind: "description".to_owned(),
+                id: "cdbcd8fb-3d6d-4f09-86ba-37a6ec1dd293".to_owned(),
+                id_gesture: "16991982-1752-4aa0-bb22-db3fbceb3780".to_owned(),
+                value: "Bryan est dans la cuisine".to_owned(),
+                langs: vec!["fr".to_owned()],
+            },
+            MissingTranslation {
+                kind: "meaning".to_owned(),
+                id: "59c25147-021e-4584-9c35-97cbf060cc89".to_owned(),
+                id_gesture: "ce27c124-e47b-490f-b8fe-3f37d5dbbef6".to_owned(),
+                value: "Un petit meaning".to_owned(),
+                langs: vec!["fr".to_owned()],
+            },
+        ]
+    );
+
+    let res = client
+        .get(&format!(
+            "{}/translations/missing?lang=fr&max=1&page=2",
+            address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let missing: MissingTranslations = res.json().await.unwrap();
+    assert_eq!(missing.total, 1);
+    assert!(missing.items.is_empty());
+
+    let res = client
+        .get(&format!("{}/translations/missing?lang=xx", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
@@ -18,7 +18,7 @@ pub mod setup {
         pub static ref CONF: ConfTest = {
             dotenv::dotenv().ok();
 
-            let db_pool = mon_oeil_db::connect_db();
+            let db_pool = mon_oeil_db::connect_db().expect("Need db env vars for test.");
             ConfTest {
                 db_pool,
                 hs256_private_key: var("HS256_PRIVATE_KEY")
@@ -31,6 +31,18 @@ pub mod setup {
         spawn_app_with_storage(Storage::default)
     }
 
+    /// App whose storage only builds picture urls, on monoielfakeapp.com
+    pub fn spawn_app_with_fake_urls() -> String {
+        spawn_app_with_storage(|| {
+            let mut storage = Storage::default();
+            storage
+                .expect_get_url()
+                .returning(|id, fmt| format!("http://monoielfakeapp.com/{}.{}", id, fmt));
+
+            storage
+        })
+    }
+
     pub fn spawn_app_with_storage(build_storage: fn() -> Storage) -> String {
         let _ = env_logger::builder().is_test(true).try_init();
 
@@ -38,7 +50,8 @@ pub mod setup {
         // We retrieve the port assigned to us by the OS
         let port = listener.local_addr().unwrap().port();
 
-        let server = mon_oeil_srv::run_with_storage(listener, build_storage)
+        let db_pool = mon_oeil_db::connect_db().expect("Need db env vars for test.");
+        let server = mon_oeil_srv::run_with_storage(listener, db_pool, build_storage)
             .expect("Failed to spawn our app.");
 
         let _ = tokio::spawn(server);
@@ -140,6 +153,14 @@ pub mod setup {
                 VALUES ('6e1ee88d-fd97-488c-9aa8-6b66a3f3e714', '16991982-1752-4aa0-bb22-db3fbceb3780', '{"fr", "us"}', 'png');"#, &[]).unwrap();
     }
 
+    pub fn insert_gesture_with_french_description() {
+        let mut client = connect();
+        client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('ce27c124-e47b-490f-b8fe-3f37d5dbbef6', '{"tag1", "tag2"}')"#, &[]).unwrap();
+        client.execute(r#"INSERT INTO descriptions(
+                id_description, id_gesture, val, langs)
+                VALUES ('2ae70884-97bd-401d-8f43-d1778d4502d2', 'ce27c124-e47b-490f-b8fe-3f37d5dbbef6', 'Il marche avec les chevaux', '{"fr"}');"#, &[]).unwrap();
+    }
+
     pub fn insert_2_gestures_some_content() {
         let mut client = connect();
         client.execute(r#"INSERT INTO gestures(id_gesture, tags) VALUES ('16991982-1752-4aa0-bb22-db3fbceb3780', '{"tag1", "jambe"}')"#, &[]).unwrap();
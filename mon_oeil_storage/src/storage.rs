@@ -39,6 +39,12 @@ impl Storage {
         Ok(())
     }
 
+    pub async fn download(&self, id: &str, img_type: &str) -> Result<Vec<u8>, StorageError> {
+        let content = Object::download(&self.bucket_name, &format!("{}.{}", id, img_type)).await?;
+
+        Ok(content)
+    }
+
     pub async fn delete(&self, id: &str, img_type: &str) -> Result<(), StorageError> {
         Object::delete(&self.bucket_name, &format!("{}.{}", id, img_type)).await?;
 
@@ -69,10 +75,12 @@ mod tests {
         let file = std::fs::read("asset/dummy.png").unwrap();
 
         let res_upload = storage.upload("test", file.clone(), "png").await.is_ok();
+        let res_download = storage.download("test", "png").await;
         let res_delete = storage.delete("test", "png").await.is_ok();
 
         // assert at the end to have more chance to stay clean in our test storage
         assert!(res_upload);
+        assert_eq!(res_download, Ok(file));
         assert!(res_delete);
     }
 }